@@ -0,0 +1,46 @@
+/// Charge level of the machine's battery
+pub struct Battery {
+    pub percent: u8,
+    pub charging: bool,
+}
+
+impl Battery {
+    /// Whether the battery is running down below `threshold` percent
+    pub fn is_low(&self, threshold: u8) -> bool {
+        !self.charging && self.percent < threshold
+    }
+}
+
+/// Read the first battery under `/sys/class/power_supply`, if there is one
+#[cfg(target_os = "linux")]
+pub fn read() -> Option<Battery> {
+    let supplies = std::fs::read_dir("/sys/class/power_supply").ok()?;
+
+    for supply in supplies.flatten() {
+        let path = supply.path();
+        let read = |name: &str| std::fs::read_to_string(path.join(name)).ok();
+
+        if read("type").as_deref().map(str::trim) != Some("Battery") {
+            continue;
+        }
+
+        let Some(percent) = read("capacity").and_then(|c| c.trim().parse().ok()) else {
+            continue;
+        };
+        let status = read("status").unwrap_or_default();
+
+        return Some(Battery {
+            percent,
+            // "Full" and "Not charging" both mean we're on AC power
+            charging: status.trim() != "Discharging",
+        });
+    }
+
+    None
+}
+
+/// Battery levels are only read on Linux; elsewhere there's never a battery to watch
+#[cfg(not(target_os = "linux"))]
+pub fn read() -> Option<Battery> {
+    None
+}
@@ -0,0 +1,61 @@
+use std::env;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Put `text` on the system clipboard with the platform's command-line tool
+pub fn copy(text: &str) -> Result<(), String> {
+    if headless() {
+        return Err("no display to own a clipboard".to_string());
+    }
+
+    let mut tried = Vec::new();
+
+    for (program, args) in tools() {
+        let mut child = match Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn() {
+            Ok(child) => child,
+            // Not installed, try the next one
+            Err(_) => {
+                tried.push(program);
+                continue;
+            },
+        };
+
+        // Dropping stdin closes it, so the tool sees the end of the text
+        let written = child.stdin.take()
+            .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+
+        return match child.wait() {
+            Ok(status) if status.success() && written => Ok(()),
+            Ok(status) => Err(format!("{} exited with {}", program, status)),
+            Err(e) => Err(e.to_string()),
+        };
+    }
+
+    Err(format!("no clipboard tool found (tried {})", tried.join(", ")))
+}
+
+/// On Linux and the BSDs, the clipboard belongs to a Wayland or X11 session;
+/// over SSH or on a bare console there's nobody to hand the text to
+fn headless() -> bool {
+    cfg!(unix) && !cfg!(target_os = "macos")
+        && env::var_os("WAYLAND_DISPLAY").is_none()
+        && env::var_os("DISPLAY").is_none()
+}
+
+/// Candidate tools and their arguments for this platform
+fn tools() -> Vec<(&'static str, &'static [&'static str])> {
+    if cfg!(target_os = "macos") {
+        vec![("pbcopy", &[])]
+    } else if cfg!(windows) {
+        vec![("clip", &[])]
+    } else if env::var_os("WAYLAND_DISPLAY").is_some() {
+        vec![("wl-copy", &[]), ("xclip", &["-selection", "clipboard"]), ("xsel", &["--clipboard", "--input"])]
+    } else {
+        vec![("xclip", &["-selection", "clipboard"]), ("xsel", &["--clipboard", "--input"])]
+    }
+}
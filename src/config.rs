@@ -0,0 +1,720 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use dirs::config_dir;
+use crate::emoji::{EmojiMode, EmojiPin};
+use crate::history::{AttributeBy, LogFormat, Rounding};
+use crate::sound::{Overlap, SoundTheme};
+use crate::status::Spinner;
+use crate::stats::WeekStart;
+
+/// A single value from the config file
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl Value {
+    /// Interpret the value as a non-negative whole number
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Int(n) if *n >= 0 => Some(*n as u64),
+            _ => None,
+        }
+    }
+
+    /// Interpret the value as a number, whole or not
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Int(n) => Some(*n as f64),
+            Value::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    /// Interpret the value as a boolean
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Interpret the value as a string
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Write the value as it would appear in the config file
+    fn to_toml(&self) -> String {
+        match self {
+            Value::Str(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+            Value::Int(n) => n.to_string(),
+            Value::Float(f) => f.to_string(),
+            Value::Bool(b) => b.to_string(),
+        }
+    }
+}
+
+/// Flattened `key = value` pairs, with `[section]` names joined by dots
+pub type Table = BTreeMap<String, Value>;
+
+/// What kind of value a config key takes
+#[derive(Clone, Copy)]
+enum Kind {
+    Str,
+    Bool,
+    /// Any non-negative whole number
+    Count,
+    /// A whole number greater than zero, like a duration
+    Positive,
+    /// A whole number from 0 to 100
+    Percent,
+    /// An hour of the day, 0 to 23
+    Hour,
+    /// A number above 0 and up to 1
+    Ratio,
+    /// One of a fixed set of strings
+    Choice(&'static [&'static str]),
+    /// Either true or false, or a string
+    BoolOrStr,
+}
+
+/// Every key the config file understands
+const KEYS: &[(&str, Kind)] = &[
+    ("min_count_minutes", Kind::Count),
+    ("task", Kind::Str),
+    ("untitled_task", Kind::Str),
+    ("work", Kind::Positive),
+    ("short_break", Kind::Positive),
+    ("long_break", Kind::Positive),
+    ("break_ratio", Kind::Ratio),
+    ("sessions", Kind::Positive),
+    ("lang", Kind::Str),
+    ("email_to", Kind::Str),
+    ("sendmail_command", Kind::Str),
+    ("pause_on_low_battery", Kind::Percent),
+    ("alert_overlap", Kind::Choice(&["queue", "drop"])),
+    ("alert_repeat", Kind::Positive),
+    ("assets_dir", Kind::Str),
+    ("sound", Kind::Str),
+    ("sound_dir", Kind::Str),
+    ("sound_theme", Kind::Choice(&["chime", "bell", "retro"])),
+    ("no_sound", Kind::Bool),
+    ("no_notify", Kind::Bool),
+    ("online_tip", Kind::Bool),
+    ("online_tip_url", Kind::Str),
+    ("online_tip_field", Kind::Str),
+    ("copy", Kind::Bool),
+    ("track_window", Kind::Bool),
+    ("copy_format", Kind::Str),
+    ("warn_before", Kind::Count),
+    ("speak_interval", Kind::Positive),
+    ("progress_notification", Kind::Bool),
+    ("status_fifo", Kind::Str),
+    ("haptic", Kind::Bool),
+    ("no_motivation", Kind::Bool),
+    ("milestones", Kind::Bool),
+    ("blind", Kind::Bool),
+    ("emoji", Kind::Choice(&["on", "off", "auto"])),
+    ("pin_emoji", Kind::BoolOrStr),
+    ("tip_on_start", Kind::Bool),
+    ("refresh", Kind::Positive),
+    ("log_breaks", Kind::Bool),
+    ("unit_label", Kind::Str),
+    ("unit_label_plural", Kind::Str),
+    ("week_start", Kind::Choice(&["monday", "sunday"])),
+    ("no_interrupt_logging", Kind::Bool),
+    ("daily_goal", Kind::Positive),
+    ("max_daily_minutes", Kind::Positive),
+    ("min_break_gap", Kind::Positive),
+    ("eod_summary", Kind::Bool),
+    ("banner", Kind::Bool),
+    ("banner_file", Kind::Str),
+    ("catch_up", Kind::Bool),
+    ("catch_up_hour", Kind::Hour),
+    ("count_sessions", Kind::Bool),
+    ("count_sessions_every", Kind::Positive),
+    ("grace_period", Kind::Count),
+    ("log_duration_rounding", Kind::Choice(&["nearest", "up", "down"])),
+    ("log_format", Kind::Choice(&["text", "jsonl"])),
+    ("attribute_by", Kind::Choice(&["start", "end"])),
+    ("idle_exit_minutes", Kind::Count),
+    ("progress_bar", Kind::Bool),
+    ("progress_chars", Kind::Str),
+    ("progress_width", Kind::Positive),
+    ("progress_template", Kind::Str),
+    ("spinner", Kind::Choice(&["braille", "dots", "none"])),
+    ("team_endpoint", Kind::Str),
+    ("team_user", Kind::Str),
+    ("project_file", Kind::Str),
+    // Accepted in every build so configs can be shared, but only read with the `syslog` feature
+    ("syslog", Kind::Bool),
+];
+
+/// Keys allowed in a `[presets.<name>]` section
+const PRESET_KEYS: &[(&str, Kind)] = &[
+    ("sessions", Kind::Positive),
+    ("work", Kind::Positive),
+    ("short_break", Kind::Positive),
+    ("long_break", Kind::Positive),
+    ("task", Kind::Str),
+    ("confirm_each", Kind::Bool),
+    ("long_break_after_minutes", Kind::Positive),
+];
+
+/// A named schedule from a `[presets.<name>]` section; `None` falls back to the usual settings
+#[derive(Clone, Default)]
+pub struct Preset {
+    pub sessions: Option<u32>,
+    pub work: Option<u64>,
+    pub short_break: Option<u64>,
+    pub long_break: Option<u64>,
+    pub task: Option<String>,
+    pub confirm_each: Option<bool>,
+    pub long_break_after_minutes: Option<u64>,
+}
+
+/// Settings read from the config file; `None` means "not set"
+#[derive(Clone, Default)]
+pub struct Config {
+    pub min_count_minutes: Option<u64>,
+    pub task: Option<String>,
+    pub untitled_task: Option<String>,
+    pub work: Option<u64>,
+    pub short_break: Option<u64>,
+    pub long_break: Option<u64>,
+    pub break_ratio: Option<f64>,
+    pub sessions: Option<u32>,
+    pub lang: Option<String>,
+    pub email_to: Option<String>,
+    pub sendmail_command: Option<String>,
+    pub pause_on_low_battery: Option<u8>,
+    pub alert_overlap: Option<Overlap>,
+    pub alert_repeat: Option<u32>,
+    pub assets_dir: Option<PathBuf>,
+    pub sound: Option<PathBuf>,
+    pub sound_dir: Option<PathBuf>,
+    pub sound_theme: Option<SoundTheme>,
+    pub no_sound: Option<bool>,
+    pub no_notify: Option<bool>,
+    pub online_tip: Option<bool>,
+    pub online_tip_url: Option<String>,
+    pub online_tip_field: Option<String>,
+    pub copy: Option<bool>,
+    pub track_window: Option<bool>,
+    pub copy_format: Option<String>,
+    pub warn_before: Option<u64>,
+    pub speak_interval: Option<u64>,
+    pub progress_notification: Option<bool>,
+    pub status_fifo: Option<PathBuf>,
+    pub haptic: Option<bool>,
+    pub no_motivation: Option<bool>,
+    pub milestones: Option<bool>,
+    pub blind: Option<bool>,
+    pub emoji: Option<EmojiMode>,
+    pub pin_emoji: Option<EmojiPin>,
+    pub tip_on_start: Option<bool>,
+    pub refresh: Option<u64>,
+    pub log_breaks: Option<bool>,
+    pub unit_label: Option<String>,
+    pub unit_label_plural: Option<String>,
+    pub week_start: Option<WeekStart>,
+    pub no_interrupt_logging: Option<bool>,
+    pub daily_goal: Option<u64>,
+    pub max_daily_minutes: Option<u64>,
+    pub min_break_gap: Option<u64>,
+    pub eod_summary: Option<bool>,
+    pub banner: Option<bool>,
+    pub banner_file: Option<PathBuf>,
+    pub catch_up: Option<bool>,
+    pub catch_up_hour: Option<u32>,
+    pub count_sessions: Option<bool>,
+    pub count_sessions_every: Option<u64>,
+    pub grace_period: Option<u64>,
+    pub log_duration_rounding: Option<Rounding>,
+    pub log_format: Option<LogFormat>,
+    pub attribute_by: Option<AttributeBy>,
+    pub idle_exit_minutes: Option<u64>,
+    pub progress_bar: Option<bool>,
+    pub progress_chars: Option<String>,
+    pub progress_width: Option<u64>,
+    pub progress_template: Option<String>,
+    pub spinner: Option<Spinner>,
+    pub team_endpoint: Option<String>,
+    pub team_user: Option<String>,
+    pub project_file: Option<PathBuf>,
+    #[cfg(feature = "syslog")]
+    pub syslog: Option<bool>,
+    pub presets: BTreeMap<String, Preset>,
+}
+
+impl Config {
+    /// Build a config from a parsed table, ignoring values of the wrong type
+    pub fn from_table(table: &Table) -> Config {
+        Config {
+            min_count_minutes: table.get("min_count_minutes").and_then(Value::as_u64),
+            task: table.get("task").and_then(Value::as_str).map(str::to_string),
+            untitled_task: table.get("untitled_task").and_then(Value::as_str).map(str::to_string),
+            work: table.get("work").and_then(Value::as_u64),
+            short_break: table.get("short_break").and_then(Value::as_u64),
+            long_break: table.get("long_break").and_then(Value::as_u64),
+            break_ratio: table.get("break_ratio").and_then(Value::as_f64),
+            sessions: table.get("sessions").and_then(Value::as_u64).map(|n| n as u32),
+            lang: table.get("lang").and_then(Value::as_str).map(str::to_string),
+            email_to: table.get("email_to").and_then(Value::as_str).map(str::to_string),
+            sendmail_command: table.get("sendmail_command").and_then(Value::as_str).map(str::to_string),
+            pause_on_low_battery: table.get("pause_on_low_battery").and_then(Value::as_u64).map(|n| n.min(100) as u8),
+            alert_overlap: table.get("alert_overlap").and_then(Value::as_str).and_then(Overlap::from_name),
+            alert_repeat: table.get("alert_repeat").and_then(Value::as_u64).map(|n| n.min(u32::MAX as u64) as u32),
+            assets_dir: table.get("assets_dir").and_then(Value::as_str).map(expand_home),
+            sound: table.get("sound").and_then(Value::as_str).map(expand_home),
+            sound_dir: table.get("sound_dir").and_then(Value::as_str).map(expand_home),
+            sound_theme: table.get("sound_theme").and_then(Value::as_str).and_then(SoundTheme::from_name),
+            no_sound: table.get("no_sound").and_then(Value::as_bool),
+            no_notify: table.get("no_notify").and_then(Value::as_bool),
+            online_tip: table.get("online_tip").and_then(Value::as_bool),
+            online_tip_url: table.get("online_tip_url").and_then(Value::as_str).map(str::to_string),
+            online_tip_field: table.get("online_tip_field").and_then(Value::as_str).map(str::to_string),
+            copy: table.get("copy").and_then(Value::as_bool),
+            track_window: table.get("track_window").and_then(Value::as_bool),
+            copy_format: table.get("copy_format").and_then(Value::as_str).map(str::to_string),
+            warn_before: table.get("warn_before").and_then(Value::as_u64),
+            speak_interval: table.get("speak_interval").and_then(Value::as_u64),
+            progress_notification: table.get("progress_notification").and_then(Value::as_bool),
+            status_fifo: table.get("status_fifo").and_then(Value::as_str).map(expand_home),
+            haptic: table.get("haptic").and_then(Value::as_bool),
+            no_motivation: table.get("no_motivation").and_then(Value::as_bool),
+            milestones: table.get("milestones").and_then(Value::as_bool),
+            blind: table.get("blind").and_then(Value::as_bool),
+            emoji: table.get("emoji").and_then(Value::as_str).and_then(EmojiMode::from_name),
+            pin_emoji: table.get("pin_emoji").and_then(|value| match value.as_bool() {
+                Some(true) => Some(EmojiPin::PerTimer),
+                Some(false) => Some(EmojiPin::Off),
+                None => value.as_str().map(|emoji| EmojiPin::Fixed(emoji.to_string())),
+            }),
+            tip_on_start: table.get("tip_on_start").and_then(Value::as_bool),
+            refresh: table.get("refresh").and_then(Value::as_u64),
+            log_breaks: table.get("log_breaks").and_then(Value::as_bool),
+            unit_label: table.get("unit_label").and_then(Value::as_str).map(str::to_string),
+            unit_label_plural: table.get("unit_label_plural").and_then(Value::as_str).map(str::to_string),
+            week_start: table.get("week_start").and_then(Value::as_str).and_then(WeekStart::from_name),
+            no_interrupt_logging: table.get("no_interrupt_logging").and_then(Value::as_bool),
+            daily_goal: table.get("daily_goal").and_then(Value::as_u64),
+            max_daily_minutes: table.get("max_daily_minutes").and_then(Value::as_u64),
+            min_break_gap: table.get("min_break_gap").and_then(Value::as_u64),
+            eod_summary: table.get("eod_summary").and_then(Value::as_bool),
+            banner: table.get("banner").and_then(Value::as_bool),
+            banner_file: table.get("banner_file").and_then(Value::as_str).map(expand_home),
+            catch_up: table.get("catch_up").and_then(Value::as_bool),
+            catch_up_hour: table.get("catch_up_hour").and_then(Value::as_u64).map(|hour| hour as u32),
+            count_sessions: table.get("count_sessions").and_then(Value::as_bool),
+            count_sessions_every: table.get("count_sessions_every").and_then(Value::as_u64),
+            grace_period: table.get("grace_period").and_then(Value::as_u64),
+            log_duration_rounding: table.get("log_duration_rounding").and_then(Value::as_str).and_then(Rounding::from_name),
+            log_format: table.get("log_format").and_then(Value::as_str).and_then(LogFormat::from_name),
+            attribute_by: table.get("attribute_by").and_then(Value::as_str).and_then(AttributeBy::from_name),
+            idle_exit_minutes: table.get("idle_exit_minutes").and_then(Value::as_u64),
+            progress_bar: table.get("progress_bar").and_then(Value::as_bool),
+            progress_chars: table.get("progress_chars").and_then(Value::as_str).map(str::to_string),
+            progress_width: table.get("progress_width").and_then(Value::as_u64),
+            progress_template: table.get("progress_template").and_then(Value::as_str).map(str::to_string),
+            spinner: table.get("spinner").and_then(Value::as_str).and_then(Spinner::from_name),
+            team_endpoint: table.get("team_endpoint").and_then(Value::as_str).map(str::to_string),
+            team_user: table.get("team_user").and_then(Value::as_str).map(str::to_string),
+            project_file: table.get("project_file").and_then(Value::as_str).map(expand_home),
+            #[cfg(feature = "syslog")]
+            syslog: table.get("syslog").and_then(Value::as_bool),
+            presets: presets(table),
+        }
+    }
+}
+
+/// Split a `presets.<name>.<key>` key into the preset name and key
+fn preset_key(key: &str) -> Option<(&str, &str)> {
+    key.strip_prefix("presets.")?.rsplit_once('.')
+}
+
+/// Gather the `[presets.<name>]` sections into named presets
+fn presets(table: &Table) -> BTreeMap<String, Preset> {
+    let mut presets: BTreeMap<String, Preset> = BTreeMap::new();
+
+    for (key, value) in table {
+        let Some((name, field)) = preset_key(key) else {
+            continue;
+        };
+        let preset = presets.entry(name.to_string()).or_default();
+
+        match field {
+            "sessions" => preset.sessions = value.as_u64().map(|n| n as u32),
+            "work" => preset.work = value.as_u64(),
+            "short_break" => preset.short_break = value.as_u64(),
+            "long_break" => preset.long_break = value.as_u64(),
+            "task" => preset.task = value.as_str().map(str::to_string),
+            "confirm_each" => preset.confirm_each = value.as_bool(),
+            "long_break_after_minutes" => preset.long_break_after_minutes = value.as_u64(),
+            _ => (),
+        }
+    }
+
+    presets
+}
+
+/// Turn a path from the config into a `PathBuf`, expanding a leading `~/`
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Location of the global config file (`~/.config/pomodoro_rs/config.toml`)
+pub fn global_config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("pomodoro_rs").join("config.toml"))
+}
+
+/// Set top-level keys in the config file at `path`, creating it if needed. Lines already
+/// setting one of the keys are replaced; the rest are added before the first section,
+/// so comments, other keys and presets are kept as they were.
+pub fn set_values(path: &Path, values: &[(&str, Value)]) -> Result<(), String> {
+    let contents = fs::read_to_string(path).unwrap_or_default();
+    let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+    let line_for = |key: &str, value: &Value| format!("{} = {}", key, value.to_toml());
+
+    // Sections start at the first `[header]`
+    let top_level = lines.iter()
+        .position(|line| strip_comment(line).trim().starts_with('['))
+        .unwrap_or(lines.len());
+
+    let mut missing = Vec::new();
+    for (key, value) in values {
+        let existing = lines[..top_level].iter().position(|line| {
+            strip_comment(line).split_once('=')
+                .is_some_and(|(name, _)| name.trim().trim_matches('"') == *key)
+        });
+        match existing {
+            Some(index) => lines[index] = line_for(key, value),
+            None => missing.push(line_for(key, value)),
+        }
+    }
+
+    // New keys go straight after the last top-level line, keeping
+    // a blank line between them and the first section
+    let mut insert_at = top_level;
+    while insert_at > 0 && lines[insert_at - 1].trim().is_empty() {
+        insert_at -= 1;
+    }
+    if !missing.is_empty() && insert_at == top_level && top_level < lines.len() {
+        missing.push(String::new());
+    }
+    lines.splice(insert_at..insert_at, missing);
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    fs::write(path, lines.join("\n") + "\n").map_err(|e| e.to_string())
+}
+
+/// Name of the per-project config file
+pub const PROJECT_CONFIG_NAME: &str = ".pomodoro.toml";
+
+/// Find the nearest `.pomodoro.toml`, searching from the current directory
+/// up to the repository root (or the filesystem root outside a repository)
+pub fn find_project_config() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+
+    for dir in cwd.ancestors() {
+        let candidate = dir.join(PROJECT_CONFIG_NAME);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+
+        // Don't look past the root of the current repository
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+
+    None
+}
+
+/// Keys a project's `.pomodoro.toml` may set. A cloned repository shouldn't pick commands
+/// to run, hosts to send to or paths to write, so everything else stays in the global config.
+const PROJECT_KEYS: &[&str] = &["task", "untitled_task", "work", "short_break", "long_break", "sessions", "presets"];
+
+/// Drop the keys a project config isn't allowed to set, warning about each
+fn project_keys_only(path: &Path, mut table: Table) -> Table {
+    table.retain(|key, _| {
+        let field = if preset_key(key).is_some() { "presets" } else { key.as_str() };
+        let allowed = PROJECT_KEYS.contains(&field);
+        if !allowed {
+            eprintln!("⚠️ {:?}: ignoring `{}`, a project config can only set {}", path, key, PROJECT_KEYS.join(", "));
+        }
+        allowed
+    });
+
+    table
+}
+
+/// Load the global config with the project config merged over it,
+/// falling back to defaults for anything missing or unreadable
+pub fn load_config() -> Config {
+    let mut table = global_config_path()
+        .and_then(|path| read_table(&path))
+        .unwrap_or_default();
+
+    // Project settings override global ones key by key
+    if let Some(path) = find_project_config()
+        && let Some(project) = read_table(&path) {
+        table.extend(project_keys_only(&path, project));
+    }
+
+    Config::from_table(&table)
+}
+
+/// Read and parse a config file, returning `None` if it can't be read
+pub fn read_table(path: &Path) -> Option<Table> {
+    let contents = fs::read_to_string(path).ok()?;
+
+    match parse_table(&contents) {
+        Ok(table) => Some(validate(path, table)),
+        Err(e) => {
+            eprintln!("⚠️ Ignoring {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Warn about unknown keys and out-of-range values, dropping the invalid ones
+/// so the rest of the file still applies
+fn validate(path: &Path, mut table: Table) -> Table {
+    table.retain(|key, value| {
+        // Preset sections take their own, smaller set of keys
+        let (field, keys) = match preset_key(key) {
+            Some((_, field)) => (field, PRESET_KEYS),
+            None => (key.as_str(), KEYS),
+        };
+
+        let Some((_, kind)) = keys.iter().find(|(name, _)| *name == field) else {
+            match suggest(field, keys) {
+                Some(known) => eprintln!("⚠️ {:?}: unknown key `{}` (did you mean `{}`?)", path, key, known),
+                None => eprintln!("⚠️ {:?}: unknown key `{}`", path, key),
+            }
+            return false;
+        };
+
+        let problem = match kind {
+            Kind::Str if value.as_str().is_none() => Some("must be a string".to_string()),
+            Kind::Bool if value.as_bool().is_none() => Some("must be true or false".to_string()),
+            Kind::Count if value.as_u64().is_none() => Some("must be a whole number".to_string()),
+            Kind::Positive if value.as_u64().is_none_or(|n| n == 0) => Some("must be a whole number greater than 0".to_string()),
+            Kind::Percent if value.as_u64().is_none_or(|n| n > 100) => Some("must be a whole number from 0 to 100".to_string()),
+            Kind::Hour if value.as_u64().is_none_or(|n| n > 23) => Some("must be an hour from 0 to 23".to_string()),
+            Kind::Ratio if !value.as_f64().is_some_and(|n| n > 0.0 && n <= 1.0) =>
+                Some("must be a number above 0 and up to 1".to_string()),
+            Kind::BoolOrStr if value.as_bool().is_none() && value.as_str().is_none() =>
+                Some("must be true, false or a string".to_string()),
+            Kind::Choice(choices) if !value.as_str().is_some_and(|v| choices.iter().any(|c| c.eq_ignore_ascii_case(v))) =>
+                Some(format!("must be one of: {}", choices.join(", "))),
+            _ => None,
+        };
+
+        if let Some(problem) = &problem {
+            eprintln!("⚠️ {:?}: ignoring `{}`, it {}", path, key, problem);
+        }
+        problem.is_none()
+    });
+
+    table
+}
+
+/// The known key closest to a misspelled one, if any is close enough
+fn suggest(key: &str, keys: &[(&'static str, Kind)]) -> Option<&'static str> {
+    keys.iter()
+        .map(|(name, _)| (*name, edit_distance(key, name)))
+        .filter(|(name, distance)| *distance <= 2.max(name.len() / 4))
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(name, _)| name)
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Parse the small subset of TOML we support: `[section]` headers,
+/// `key = value` pairs, strings, integers, floats, booleans and `#` comments
+pub fn parse_table(contents: &str) -> Result<Table, String> {
+    let mut table = Table::new();
+    let mut section = String::new();
+
+    for (index, raw_line) in contents.lines().enumerate() {
+        let line_no = index + 1;
+        let line = strip_comment(raw_line).trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        // Section header
+        if line.starts_with('[') {
+            if !line.ends_with(']') {
+                return Err(format!("line {}: unterminated section header", line_no));
+            }
+            // Allow quoted parts, like `[presets."deep work"]`
+            section = line[1..line.len() - 1].trim().replace('"', "");
+            continue;
+        }
+
+        let (key, value) = line.split_once('=')
+            .ok_or_else(|| format!("line {}: expected `key = value`", line_no))?;
+
+        let key = key.trim().trim_matches('"');
+        if key.is_empty() {
+            return Err(format!("line {}: missing key", line_no));
+        }
+
+        let value = parse_value(value.trim())
+            .ok_or_else(|| format!("line {}: invalid value for `{}`", line_no, key))?;
+
+        let full_key = if section.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", section, key)
+        };
+
+        table.insert(full_key, value);
+    }
+
+    Ok(table)
+}
+
+/// Remove a trailing `#` comment, leaving `#` inside quoted strings alone
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => (),
+        }
+    }
+
+    line
+}
+
+/// Parse a single value
+fn parse_value(raw: &str) -> Option<Value> {
+    if raw.len() >= 2 && raw.starts_with('"') && raw.ends_with('"') {
+        let inner = &raw[1..raw.len() - 1];
+        return Some(Value::Str(inner.replace("\\\"", "\"").replace("\\\\", "\\")));
+    }
+
+    match raw {
+        "true" => return Some(Value::Bool(true)),
+        "false" => return Some(Value::Bool(false)),
+        _ => (),
+    }
+
+    let number = raw.replace('_', "");
+    if let Ok(n) = number.parse::<i64>() {
+        return Some(Value::Int(n));
+    }
+    if let Ok(f) = number.parse::<f64>() {
+        return Some(Value::Float(f));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(contents: &str) -> Table {
+        parse_table(contents).unwrap()
+    }
+
+    #[test]
+    fn project_config_keeps_allowed_keys() {
+        let project = table("task = \"Thesis\"\nwork = 50\n[presets.deep]\nwork = 90\n");
+        let kept = project_keys_only(Path::new(".pomodoro.toml"), project.clone());
+        assert_eq!(kept, project);
+    }
+
+    #[test]
+    fn project_config_drops_other_keys() {
+        let project = table("work = 50\nsendmail_command = \"sh evil.sh\"\nteam_endpoint = \"http://example.com\"\nstatus_fifo = \"/tmp/x\"\n");
+        let kept = project_keys_only(Path::new(".pomodoro.toml"), project);
+        assert_eq!(kept.keys().collect::<Vec<_>>(), ["work"]);
+    }
+
+    fn validated(contents: &str) -> Table {
+        validate(Path::new("config.toml"), table(contents))
+    }
+
+    #[test]
+    fn unknown_keys_are_dropped() {
+        let kept = validated("work = 50
+wrok_minutes = 30
+[presets.deep]
+work = 90
+banner = true
+");
+        assert_eq!(kept.keys().collect::<Vec<_>>(), ["presets.deep.work", "work"]);
+    }
+
+    #[test]
+    fn typos_get_a_suggestion() {
+        assert_eq!(suggest("wrok", KEYS), Some("work"));
+        assert_eq!(suggest("short_brake", KEYS), Some("short_break"));
+        assert_eq!(suggest("lang_break", KEYS), Some("long_break"));
+        // Presets only suggest preset keys
+        assert_eq!(suggest("confirm_eahc", PRESET_KEYS), Some("confirm_each"));
+        assert_eq!(suggest("confirm_eahc", KEYS), None);
+        assert_eq!(suggest("colour_scheme", KEYS), None);
+    }
+
+    #[test]
+    fn edit_distance_counts_single_changes() {
+        assert_eq!(edit_distance("work", "work"), 0);
+        assert_eq!(edit_distance("work", "wrk"), 1);
+        assert_eq!(edit_distance("work", "worker"), 2);
+        assert_eq!(edit_distance("wrok", "work"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn values_out_of_range_are_dropped() {
+        let kept = validated("work = 0\nshort_break = -5\nsessions = 4\n\
+                              pause_on_low_battery = 101\ncatch_up_hour = 24\nbreak_ratio = 1.5\n\
+                              emoji = \"sometimes\"\nbanner = \"yes\"\n");
+        assert_eq!(kept.keys().collect::<Vec<_>>(), ["sessions"]);
+    }
+
+    #[test]
+    fn values_in_range_are_kept() {
+        let contents = "work = 1\npause_on_low_battery = 100\ncatch_up_hour = 0\nbreak_ratio = 1\n\
+                        grace_period = 0\nemoji = \"OFF\"\npin_emoji = \"🍅\"\nblind = false\n";
+        assert_eq!(validated(contents), table(contents));
+    }
+}
@@ -0,0 +1,54 @@
+use std::env;
+use clap::ValueEnum;
+
+/// Whether messages are decorated with emoji
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum EmojiMode {
+    On,
+    /// Plain ASCII markers instead
+    Off,
+    /// On, unless the terminal is known to show emoji as boxes
+    Auto,
+}
+
+impl EmojiMode {
+    /// Parse a config value
+    pub fn from_name(name: &str) -> Option<EmojiMode> {
+        EmojiMode::from_str(name, true).ok()
+    }
+
+    /// Resolve `Auto` against the current terminal
+    pub fn enabled(self) -> bool {
+        match self {
+            EmojiMode::On => true,
+            EmojiMode::Off => false,
+            EmojiMode::Auto => terminal_supports_emoji(),
+        }
+    }
+}
+
+/// How the emoji shown while a timer runs is chosen
+#[derive(Clone, Debug, PartialEq)]
+pub enum EmojiPin {
+    /// A new random one each time
+    Off,
+    /// One random pick as each timer starts, kept until it ends
+    PerTimer,
+    /// Always this one
+    Fixed(String),
+}
+
+/// Guess whether the terminal can draw emoji. Legacy Windows consoles (plain
+/// `cmd.exe` or PowerShell in conhost) can't, while Windows Terminal, VS Code,
+/// ConEmu and mintty all announce themselves through the environment.
+/// The Linux virtual console can't either.
+fn terminal_supports_emoji() -> bool {
+    if cfg!(windows) {
+        return env::var_os("WT_SESSION").is_some()
+            || env::var_os("TERM_PROGRAM").is_some()
+            || env::var("ConEmuANSI").is_ok_and(|value| value == "ON")
+            || env::var_os("TERM").is_some();
+    }
+
+    env::var("TERM").map_or(true, |term| term != "linux")
+}
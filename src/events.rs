@@ -0,0 +1,91 @@
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use chrono::{DateTime, Local, SecondsFormat};
+use crate::json;
+
+/// Set by `--log-json-stdout`; until then nothing is printed
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// The timer that was last started, so an interrupt can say what it cut short
+static CURRENT: Mutex<Option<Current>> = Mutex::new(None);
+
+struct Current {
+    timer_type: String,
+    task: Option<String>,
+    started: DateTime<Local>,
+}
+
+/// Print session events as JSON lines on stdout from now on
+pub fn enable() {
+    ENABLED.store(true, Ordering::SeqCst);
+}
+
+/// A work session started, planned to last `seconds` (`None` for a stopwatch)
+pub fn work_started(timer_type: &str, task: &str, seconds: Option<u64>) {
+    start("start", timer_type, Some(task), seconds);
+}
+
+pub fn break_started(timer_type: &str, seconds: u64) {
+    start("break_start", timer_type, None, Some(seconds));
+}
+
+/// A work session ran its course; `minutes` is what was (or would have been) logged
+pub fn work_completed(timer_type: &str, task: &str, minutes: u64) {
+    finish("complete", timer_type, Some(task), minutes);
+}
+
+pub fn break_ended(timer_type: &str, minutes: u64) {
+    finish("break_end", timer_type, None, minutes);
+}
+
+/// The running timer was stopped early: skipped, or quit with Ctrl+C
+pub fn interrupted() {
+    let Some(current) = CURRENT.lock().ok().and_then(|mut current| current.take()) else {
+        return;
+    };
+
+    let elapsed = Local::now().signed_duration_since(current.started).num_seconds().max(0);
+    emit("interrupt", &current.timer_type, current.task.as_deref(), &format!(",\"elapsed_seconds\":{}", elapsed));
+}
+
+fn start(event: &str, timer_type: &str, task: Option<&str>, seconds: Option<u64>) {
+    if !ENABLED.load(Ordering::SeqCst) {
+        return;
+    }
+    if let Ok(mut current) = CURRENT.lock() {
+        *current = Some(Current {
+            timer_type: timer_type.to_string(),
+            task: task.map(str::to_string),
+            started: Local::now(),
+        });
+    }
+
+    let seconds = seconds.map_or(String::new(), |seconds| format!(",\"seconds\":{}", seconds));
+    emit(event, timer_type, task, &seconds);
+}
+
+fn finish(event: &str, timer_type: &str, task: Option<&str>, minutes: u64) {
+    if let Ok(mut current) = CURRENT.lock() {
+        *current = None;
+    }
+    emit(event, timer_type, task, &format!(",\"minutes\":{}", minutes));
+}
+
+/// Print one event line and flush it, so a reader at the other end of a pipe sees it now
+fn emit(event: &str, timer_type: &str, task: Option<&str>, extra: &str) {
+    if !ENABLED.load(Ordering::SeqCst) {
+        return;
+    }
+
+    let task = task.map_or(String::new(), |task| format!(",\"task\":{}", json::string(task)));
+    let line = format!("{{\"event\":{},\"time\":\"{}\",\"type\":{}{}{}}}\n",
+                       json::string(event),
+                       Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+                       json::string(timer_type),
+                       task,
+                       extra);
+
+    let mut stdout = io::stdout().lock();
+    let _ = stdout.write_all(line.as_bytes()).and_then(|_| stdout.flush());
+}
@@ -0,0 +1,57 @@
+use chrono::{Duration, NaiveDate};
+use clap::ValueEnum;
+use colored::*;
+use crate::history::{self, COMPLETED_DIR};
+
+/// Output formats for `export`
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum ExportFormat {
+    /// Time-blocked list of sessions: start–end and task
+    Agenda,
+}
+
+/// Print the sessions logged on `date` in the chosen format; a day with nothing logged is an error
+pub fn run_export(date: NaiveDate, format: ExportFormat) -> Result<(), String> {
+    if history::read_day(COMPLETED_DIR, date).is_empty() {
+        return Err(format!("No sessions logged on {}", date));
+    }
+
+    let output = match format {
+        ExportFormat::Agenda => agenda(date),
+    };
+    print!("{}", output);
+    Ok(())
+}
+
+/// One line per session, start to end. Entries are logged when a session
+/// completes, so unless the entry records its start, the start is the logged
+/// time minus the session length. Older entries without a length show `--:--`
+/// as their start. Text logs hold wall-clock times, so a session spanning a
+/// DST change starts an hour off.
+pub fn agenda(date: NaiveDate) -> String {
+    let entries = history::read_day(COMPLETED_DIR, date);
+    let mut out = format!("{}\n", date.format("%A %Y-%m-%d").to_string().bold());
+
+    let width = entries.iter()
+        .map(|entry| entry.minutes.map_or(1, |minutes| minutes.to_string().len()))
+        .max()
+        .unwrap_or(1);
+
+    for entry in &entries {
+        let start = match (entry.started, entry.minutes) {
+            (Some(started), _) => started.format("%H:%M").to_string(),
+            (None, Some(minutes)) => (entry.time - Duration::minutes(minutes as i64)).format("%H:%M").to_string(),
+            (None, None) => "--:--".to_string(),
+        };
+        let length = entry.minutes.map_or("?".to_string(), |minutes| minutes.to_string());
+
+        out.push_str(&format!("{}–{}  {:>width$} min  {}\n",
+                              start,
+                              entry.time.format("%H:%M"),
+                              length,
+                              entry.task,
+                              width = width));
+    }
+
+    out
+}
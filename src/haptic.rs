@@ -0,0 +1,66 @@
+/// Rumble the first force-feedback device under `/dev/input`, if there is one.
+/// Blocks for the length of the rumble, since closing the device stops it.
+#[cfg(target_os = "linux")]
+pub fn rumble() {
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    use std::os::fd::AsRawFd;
+
+    /// How long the rumble lasts
+    const RUMBLE_MS: u16 = 400;
+    const EV_FF: u16 = 0x15;
+    const FF_RUMBLE: u16 = 0x50;
+    // _IOW('E', 0x80, struct ff_effect)
+    const EVIOCSFF: u32 = (1 << 30)
+        | ((std::mem::size_of::<libc::ff_effect>() as u32) << 16)
+        | ((b'E' as u32) << 8)
+        | 0x80;
+
+    let Ok(devices) = std::fs::read_dir("/dev/input") else {
+        return;
+    };
+
+    for device in devices.flatten() {
+        if !device.file_name().to_string_lossy().starts_with("event") {
+            continue;
+        }
+
+        // Usually only readable by the `input` group; skip what we can't open
+        let Ok(mut file) = OpenOptions::new().read(true).write(true).open(device.path()) else {
+            continue;
+        };
+
+        let mut effect: libc::ff_effect = unsafe { std::mem::zeroed() };
+        effect.type_ = FF_RUMBLE;
+        effect.id = -1;
+        effect.replay.length = RUMBLE_MS;
+        // The union starts with `ff_rumble_effect { strong_magnitude, weak_magnitude }`
+        unsafe {
+            let rumble = effect.u.as_mut_ptr() as *mut libc::ff_rumble_effect;
+            (*rumble).strong_magnitude = 0xc000;
+            (*rumble).weak_magnitude = 0xc000;
+        }
+
+        // Devices without force feedback reject the upload
+        if unsafe { libc::ioctl(file.as_raw_fd(), EVIOCSFF as _, &mut effect) } < 0 {
+            continue;
+        }
+
+        let mut play: libc::input_event = unsafe { std::mem::zeroed() };
+        play.type_ = EV_FF;
+        play.code = effect.id as u16;
+        play.value = 1;
+
+        let bytes = unsafe {
+            std::slice::from_raw_parts(&play as *const _ as *const u8, std::mem::size_of::<libc::input_event>())
+        };
+        if file.write_all(bytes).is_ok() {
+            std::thread::sleep(std::time::Duration::from_millis(RUMBLE_MS as u64));
+            return;
+        }
+    }
+}
+
+/// Force feedback is only supported on Linux; elsewhere this does nothing
+#[cfg(not(target_os = "linux"))]
+pub fn rumble() {}
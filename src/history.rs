@@ -0,0 +1,469 @@
+use std::fs::{self, OpenOptions, create_dir_all};
+use std::io::Write;
+use std::path::PathBuf;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveTime, SecondsFormat, Timelike};
+use clap::ValueEnum;
+use dirs::home_dir;
+use crate::json;
+
+/// Directory holding one file per day of completed work sessions
+pub const COMPLETED_DIR: &str = ".completed_tasks";
+
+/// Directory holding one file per day of started work sessions
+pub const STARTED_DIR: &str = ".started_tasks";
+
+/// Directory holding one file per day of work sessions stopped with Ctrl+C,
+/// with how long they ran out of how long they were meant to
+pub const INTERRUPTED_DIR: &str = ".interrupted_tasks";
+
+/// Directory holding one file per day of breaks abandoned to keep working
+pub const SKIPPED_BREAKS_DIR: &str = ".skipped_breaks";
+
+/// Directory holding one file per day of notes on how breaks were spent
+pub const BREAK_NOTES_DIR: &str = ".break_notes";
+
+/// File in the data dir holding the all-time number of completed sessions
+const LIFETIME_FILE: &str = "lifetime_count";
+
+/// How new lines are written to the daily log files. Either can be read back,
+/// even mixed within one file. Billable sessions, and those with a window title
+/// or an intent, are always written as JSON Lines.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum LogFormat {
+    /// `HH:MM:SS | task | N min`, or `N/P min` for an interrupted session planned to last P
+    Text,
+    /// `{"time":"HH:MM:SS","duration":N,"kind":"completed","task":"..."}`, plus
+    /// `"start"` and `"end"` ISO 8601 timestamps (to the millisecond) on completed sessions
+    /// and `"client"` and `"rate"` on billable ones, `"window"` with `--track-window`,
+    /// `"intent"` and `"achieved"` (true, false or null if unanswered) with `--intent`
+    Jsonl,
+}
+
+impl LogFormat {
+    /// Parse a config value
+    pub fn from_name(name: &str) -> Option<LogFormat> {
+        LogFormat::from_str(name, true).ok()
+    }
+}
+
+/// Which day's log a completed session goes in when it runs past midnight
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum AttributeBy {
+    /// The day it started, so a late session stays with the rest of that evening
+    Start,
+    /// The day it completed
+    End,
+}
+
+impl AttributeBy {
+    /// Parse a config value
+    pub fn from_name(name: &str) -> Option<AttributeBy> {
+        AttributeBy::from_str(name, true).ok()
+    }
+}
+
+/// How a duration in seconds is turned into the whole minutes that get logged
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rounding {
+    Nearest,
+    Up,
+    Down,
+}
+
+impl Rounding {
+    /// Parse a config value
+    pub fn from_name(name: &str) -> Option<Rounding> {
+        match name.to_ascii_lowercase().as_str() {
+            "nearest" => Some(Rounding::Nearest),
+            "up" => Some(Rounding::Up),
+            "down" => Some(Rounding::Down),
+            _ => None,
+        }
+    }
+
+    /// Whole minutes in `seconds`, rounded this way
+    pub fn minutes(self, seconds: u64) -> u64 {
+        match self {
+            Rounding::Nearest => (seconds + 30) / 60,
+            Rounding::Up => seconds.div_ceil(60),
+            Rounding::Down => seconds / 60,
+        }
+    }
+}
+
+/// Who a billable session is charged to, and at what hourly rate
+#[derive(Clone, Debug)]
+pub struct Billing {
+    pub client: String,
+    pub rate: f64,
+}
+
+/// The specific goal set for a work session, and whether it was met
+#[derive(Clone, Debug)]
+pub struct Intent {
+    pub text: String,
+    /// `None` when nobody was there to answer
+    pub achieved: Option<bool>,
+}
+
+/// What only completed sessions record: their exact start, who they're billed to,
+/// the window in focus as they started and the intent they were started with,
+/// plus which day they count toward
+pub struct Completion<'a> {
+    pub started: DateTime<Local>,
+    pub billing: Option<&'a Billing>,
+    pub window: Option<&'a str>,
+    pub intent: Option<&'a Intent>,
+    pub attribute_by: AttributeBy,
+}
+
+/// A single line from a daily log file
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub time: NaiveTime,
+    pub task: String,
+    /// Session length, for entries that record it
+    pub minutes: Option<u64>,
+    /// Intended length, for interrupted sessions
+    pub planned: Option<u64>,
+    /// Exact start of the session, for JSON Lines entries that record it
+    pub started: Option<DateTime<FixedOffset>>,
+    /// Client and rate, for billable sessions
+    pub billing: Option<Billing>,
+}
+
+/// Directory for generated files and state (`~/.local/share/pomodoro_rs` on Linux)
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("pomodoro_rs"))
+}
+
+/// Path of the daily log file for `date` inside `~/<dir_name>`
+pub fn day_file(dir_name: &str, date: NaiveDate) -> Option<PathBuf> {
+    home_dir().map(|home| home.join(dir_name).join(format!("{}.txt", date.format("%Y%m%d"))))
+}
+
+/// Append a timestamped line to today's file in `~/<dir_name>`, or to the file for the day
+/// a completion started when it's attributed by its start
+fn append_entry(dir_name: &str, kind: &str, text: &str, minutes: Option<u64>, planned: Option<u64>,
+                completion: Option<Completion>, format: LogFormat) {
+    let now = Local::now();
+    let date = match &completion {
+        Some(completion) if completion.attribute_by == AttributeBy::Start => completion.started.date_naive(),
+        _ => now.date_naive(),
+    };
+
+    let Some(file_path) = day_file(dir_name, date) else {
+        return;
+    };
+
+    // Create directory if it doesn't exist
+    if let Some(dir) = file_path.parent()
+        && create_dir_all(dir).is_err() {
+        return;
+    }
+
+    let log_entry = format_entry(now, kind, text, minutes, planned, completion, format);
+
+    // Append to the file
+    if let Ok(mut file) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file_path) {
+        let _ = file.write_all(log_entry.as_bytes());
+    }
+}
+
+/// Format one log line stamped `at`. A `completion` is only written in the JSON Lines format,
+/// which billing, a window title or an intent switches to since the text format has no room for them.
+fn format_entry(at: DateTime<Local>, kind: &str, text: &str, minutes: Option<u64>, planned: Option<u64>,
+                completion: Option<Completion>, format: LogFormat) -> String {
+    let time = at.format("%H:%M:%S");
+    let started = completion.as_ref().map(|completion| completion.started);
+    let billing = completion.as_ref().and_then(|completion| completion.billing);
+    let window = completion.as_ref().and_then(|completion| completion.window);
+    let intent = completion.as_ref().and_then(|completion| completion.intent);
+    let format = if billing.is_some() || window.is_some() || intent.is_some() { LogFormat::Jsonl } else { format };
+
+    match format {
+        // "HH:MM:SS | text", with "| N min" when there's a duration, or "| N/P min" with a planned one
+        LogFormat::Text => match (minutes, planned) {
+            (Some(minutes), Some(planned)) => format!("{} | {} | {}/{} min\n", time, text, minutes, planned),
+            (Some(minutes), None) => format!("{} | {} | {} min\n", time, text, minutes),
+            (None, _) => format!("{} | {}\n", time, text),
+        },
+        LogFormat::Jsonl => {
+            let span = started.map(|started| format!(",\"start\":\"{}\",\"end\":\"{}\"",
+                                                     started.to_rfc3339_opts(SecondsFormat::Millis, false),
+                                                     at.to_rfc3339_opts(SecondsFormat::Millis, false)));
+            let planned = planned.map(|planned| format!(",\"planned\":{}", planned));
+            let billing = billing.map(|billing| format!(",\"client\":{},\"rate\":{}",
+                                                        json::string(&billing.client), billing.rate));
+            let window = window.map(|window| format!(",\"window\":{}", json::string(window)));
+            let intent = intent.map(|intent| format!(",\"intent\":{},\"achieved\":{}",
+                                                     json::string(&intent.text),
+                                                     intent.achieved.map_or("null".to_string(), |a| a.to_string())));
+            format!("{{\"time\":\"{}\",\"duration\":{},\"kind\":{},\"task\":{}{}{}{}{}{}}}\n",
+                    time,
+                    minutes.map_or("null".to_string(), |m| m.to_string()),
+                    json::string(kind),
+                    json::string(text),
+                    planned.unwrap_or_default(),
+                    span.unwrap_or_default(),
+                    billing.unwrap_or_default(),
+                    window.unwrap_or_default(),
+                    intent.unwrap_or_default())
+        },
+    }
+}
+
+/// Log completed task to daily file as "HH:MM:SS | task_desc | N min", with the client
+/// and rate if it's billable, the focused window if tracked and the intent if one was set,
+/// returning the new all-time total
+pub fn log_completed_task(task_desc: &str, minutes: u64, completion: Completion, format: LogFormat) -> u64 {
+    append_entry(COMPLETED_DIR, "completed", task_desc, Some(minutes), None, Some(completion), format);
+    bump_lifetime_count(1)
+}
+
+/// A completed session from outside pomodoro_rs, like another app's export
+#[derive(Debug)]
+pub struct Imported {
+    pub started: DateTime<Local>,
+    pub task: String,
+    pub minutes: u64,
+}
+
+/// Add sessions that completed on `date` to its log, keeping the file in time order,
+/// and return how many were added. Sessions already logged with the same
+/// completion time and task are skipped, so importing twice is harmless.
+pub fn import_completed(date: NaiveDate, sessions: &[Imported], format: LogFormat) -> Result<usize, String> {
+    let path = day_file(COMPLETED_DIR, date).ok_or("could not determine the home directory")?;
+    let contents = fs::read_to_string(&path).unwrap_or_default();
+
+    // Lines that can't be parsed stay right after the line before them
+    let mut time = NaiveTime::MIN;
+    let mut lines: Vec<(NaiveTime, String, String)> = Vec::new();
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        let task = match parse_entry(line) {
+            Some(entry) => {
+                time = entry.time;
+                entry.task
+            },
+            None => String::new(),
+        };
+        lines.push((time, task, format!("{}\n", line)));
+    }
+
+    let mut added = 0;
+    for session in sessions {
+        let ended = session.started + chrono::Duration::minutes(session.minutes as i64);
+        // Logs keep whole seconds
+        let time = ended.time().with_nanosecond(0).unwrap_or(ended.time());
+
+        if lines.iter().any(|(logged, task, _)| *logged == time && *task == session.task) {
+            continue;
+        }
+        // Already filed under `date`, so attribution doesn't come into it
+        let completion = Completion {
+            started: session.started,
+            billing: None,
+            window: None,
+            intent: None,
+            attribute_by: AttributeBy::End,
+        };
+        let line = format_entry(ended, "completed", &session.task, Some(session.minutes), None,
+                                Some(completion), format);
+        lines.push((time, session.task.clone(), line));
+        added += 1;
+    }
+
+    if added == 0 {
+        return Ok(0);
+    }
+
+    // A stable sort leaves same-time lines in the order they were logged
+    lines.sort_by_key(|(time, _, _)| *time);
+    if let Some(dir) = path.parent() {
+        create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let contents: String = lines.into_iter().map(|(_, _, line)| line).collect();
+    fs::write(&path, contents).map_err(|e| e.to_string())?;
+
+    bump_lifetime_count(added as i64);
+    Ok(added)
+}
+
+/// Replace `{n}` in `template` with one more than the highest number completed
+/// today under that name, so untitled sessions read "Session 1", "Session 2", ...
+pub fn next_in_sequence(template: &str) -> String {
+    let Some((prefix, suffix)) = template.split_once("{n}") else {
+        return template.to_string();
+    };
+
+    let highest = read_day(COMPLETED_DIR, Local::now().date_naive()).iter()
+        .filter_map(|entry| entry.task.strip_prefix(prefix)?.strip_suffix(suffix)?.parse::<u64>().ok())
+        .max()
+        .unwrap_or(0);
+
+    format!("{}{}{}", prefix, highest + 1, suffix)
+}
+
+/// Log a work session stopped after `minutes` of the `planned` length, for fractional credit
+pub fn log_interrupted_task(task_desc: &str, minutes: u64, planned: u64, format: LogFormat) {
+    append_entry(INTERRUPTED_DIR, "interrupted", task_desc, Some(minutes), Some(planned), None, format);
+}
+
+/// Log the start of a work session, so unfinished sessions can be counted
+pub fn log_started_task(task_desc: &str, format: LogFormat) {
+    append_entry(STARTED_DIR, "started", task_desc, None, None, None, format);
+}
+
+/// Log what a finished break was spent on, as "HH:MM:SS | note | N min"
+pub fn log_break_note(note: &str, minutes: u64, format: LogFormat) {
+    append_entry(BREAK_NOTES_DIR, "break_note", note, Some(minutes), None, None, format);
+}
+
+/// Log a break that was abandoned to keep working
+pub fn log_skipped_break(break_desc: &str, format: LogFormat) {
+    append_entry(SKIPPED_BREAKS_DIR, "skipped_break", break_desc, None, None, None, format);
+}
+
+/// Read all entries logged on `date` in `~/<dir_name>`; a missing file is an empty day
+pub fn read_day(dir_name: &str, date: NaiveDate) -> Vec<Entry> {
+    let contents = day_file(dir_name, date)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .unwrap_or_default();
+
+    contents.lines().filter_map(parse_entry).collect()
+}
+
+/// Read every entry in `~/<dir_name>`, across all days
+pub fn read_all(dir_name: &str) -> Vec<Entry> {
+    let Some(dir) = home_dir().map(|home| home.join(dir_name)) else {
+        return Vec::new();
+    };
+    let Ok(files) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    files.flatten()
+        .filter_map(|file| fs::read_to_string(file.path()).ok())
+        .flat_map(|contents| contents.lines().filter_map(parse_entry).collect::<Vec<_>>())
+        .collect()
+}
+
+/// Remove the last entry from the `date` file in `~/<dir_name>`,
+/// returning it, or `None` if there was nothing to remove
+pub fn remove_last_entry(dir_name: &str, date: NaiveDate) -> Result<Option<Entry>, String> {
+    let path = day_file(dir_name, date).ok_or("could not determine the home directory")?;
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return Ok(None);
+    };
+
+    let mut lines: Vec<&str> = contents.lines().collect();
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+
+    let Some(last) = lines.pop() else {
+        return Ok(None);
+    };
+    let entry = parse_entry(last).ok_or_else(|| format!("could not parse the last line of {:?}", path))?;
+
+    let mut remaining = lines.join("\n");
+    if !remaining.is_empty() {
+        remaining.push('\n');
+    }
+    fs::write(&path, remaining).map_err(|e| e.to_string())?;
+
+    Ok(Some(entry))
+}
+
+/// Count every completed session in `~/.completed_tasks`, across all days
+pub fn count_all_completed() -> u64 {
+    let Some(dir) = home_dir().map(|home| home.join(COMPLETED_DIR)) else {
+        return 0;
+    };
+    let Ok(files) = fs::read_dir(dir) else {
+        return 0;
+    };
+
+    files.flatten()
+        .filter_map(|file| fs::read_to_string(file.path()).ok())
+        .map(|contents| contents.lines().filter_map(parse_entry).count() as u64)
+        .sum()
+}
+
+/// All-time number of completed sessions, rebuilt from the logs if the counter file is missing
+pub fn lifetime_count() -> u64 {
+    data_dir()
+        .and_then(|dir| fs::read_to_string(dir.join(LIFETIME_FILE)).ok())
+        .and_then(|contents| contents.trim().parse().ok())
+        .unwrap_or_else(count_all_completed)
+}
+
+/// Add `delta` to the all-time counter after logging or removing a session, returning the new total.
+/// A missing counter is rebuilt from the logs, which already include the change.
+pub fn bump_lifetime_count(delta: i64) -> u64 {
+    let Some(path) = data_dir().map(|dir| dir.join(LIFETIME_FILE)) else {
+        return count_all_completed();
+    };
+
+    let total = match fs::read_to_string(&path).ok().and_then(|contents| contents.trim().parse::<u64>().ok()) {
+        Some(count) => count.saturating_add_signed(delta),
+        None => count_all_completed(),
+    };
+
+    if let Some(dir) = path.parent() {
+        let _ = create_dir_all(dir);
+    }
+    let _ = fs::write(&path, format!("{}\n", total));
+
+    total
+}
+
+/// Parse a "HH:MM:SS | task_desc" line, with an optional trailing "| N min",
+/// or the JSON Lines equivalent
+fn parse_entry(line: &str) -> Option<Entry> {
+    if line.trim_start().starts_with('{') {
+        return parse_json_entry(line);
+    }
+
+    let (time, rest) = line.split_once(" | ")?;
+    let time = NaiveTime::parse_from_str(time.trim(), "%H:%M:%S").ok()?;
+
+    // Older entries have no duration field
+    let (task, minutes, planned) = match rest.rsplit_once(" | ") {
+        Some((task, field)) => match parse_minutes(field) {
+            Some((minutes, planned)) => (task, Some(minutes), planned),
+            None => (rest, None, None),
+        },
+        None => (rest, None, None),
+    };
+
+    Some(Entry { time, task: task.trim().to_string(), minutes, planned, started: None, billing: None })
+}
+
+/// Parse a `{"time":..., "duration":..., "kind":..., "task":..., "start":...}` line
+fn parse_json_entry(line: &str) -> Option<Entry> {
+    let fields = json::parse_object(line)?;
+    let time = NaiveTime::parse_from_str(fields.get("time")?, "%H:%M:%S").ok()?;
+
+    Some(Entry {
+        time,
+        task: fields.get("task").cloned().unwrap_or_default(),
+        minutes: fields.get("duration").and_then(|minutes| minutes.parse().ok()),
+        planned: fields.get("planned").and_then(|planned| planned.parse().ok()),
+        started: fields.get("start").and_then(|start| DateTime::parse_from_rfc3339(start).ok()),
+        billing: fields.get("client").zip(fields.get("rate").and_then(|rate| rate.parse().ok()))
+            .map(|(client, rate)| Billing { client: client.clone(), rate }),
+    })
+}
+
+/// Parse an "N min" duration field, or "N/P min" with the planned length
+fn parse_minutes(field: &str) -> Option<(u64, Option<u64>)> {
+    let field = field.trim().strip_suffix(" min")?.trim();
+    match field.split_once('/') {
+        Some((minutes, planned)) => Some((minutes.trim().parse().ok()?, Some(planned.trim().parse().ok()?))),
+        None => Some((field.parse().ok()?, None)),
+    }
+}
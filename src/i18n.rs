@@ -0,0 +1,215 @@
+/// All user-facing messages for one language.
+/// Empty lists and `None` templates fall back to English.
+pub struct Language {
+    pub code: &'static str,
+    pub start_work: &'static [&'static str],
+    pub during_work: &'static [&'static str],
+    pub end_work: &'static [&'static str],
+    pub start_break: &'static [&'static str],
+    pub end_break: &'static [&'static str],
+    pub tips: &'static [&'static str],
+    /// Notification shown when a work session completes. Placeholder: `{Unit}`
+    pub work_done_title: Option<&'static str>,
+    /// Used instead of `work_done_title` during a schedule. Placeholders: `{index}`, `{total}`
+    pub session_done_title: Option<&'static str>,
+    /// Placeholders: `{emoji}`, `{minutes}`, `{task}`, `{unit}`
+    pub work_done_body: Option<&'static str>,
+    /// Notification shown when a break ends
+    pub break_done_title: Option<&'static str>,
+    /// Placeholders: `{emoji}`, `{minutes}`
+    pub break_done_body: Option<&'static str>,
+}
+
+/// Language used when no other is chosen, and for any missing message
+pub const ENGLISH: Language = Language {
+    code: "en",
+    start_work: &[
+        "Time to focus! You've got this!",
+        "Let's make the most of these minutes!",
+        "The Rust crab believes in you!",
+        "Deep work mode: engaged!",
+        "Your future self will thank you for focusing now.",
+    ],
+    during_work: &[
+        "....",
+        "....",
+        "....",
+        "....",
+        "....",
+    ],
+    end_work: &[
+        "Great job! Take a well-deserved break.",
+        "Pomodoro complete! The 🦀 is proud of you!",
+        "You've earned your rest!",
+        "Excellent focus session!",
+        "Progress made! Time to recharge.",
+    ],
+    start_break: &[
+        "Break time! Rest your mind.",
+        "The 🦀 says: time to relax!",
+        "Refresh and recharge!",
+        "Stretch, hydrate, breathe!",
+        "Short breaks make long coding sessions possible.",
+    ],
+    end_break: &[
+        "Break's over! Ready to dive back in?",
+        "Time to get back to it! The 🦀 is ready!",
+        "Refreshed and ready to go!",
+        "Back to making progress!",
+        "Let's continue building amazing things!",
+    ],
+    tips: &[
+        "The Pomodoro Technique works best when you fully commit to the task during work periods.",
+        "Keep a list of small tasks to tackle during short breaks to maintain productivity momentum.",
+        "Physical activity during breaks (like stretching) can boost your energy for the next Pomodoro.",
+        "Try different Pomodoro lengths to find what works best for you - not everyone is optimal at 25 minutes.",
+        "Use Pomodoros to estimate task completion times by tracking how many you need for similar tasks.",
+        "The 'rule of three' suggests focusing on completing just three main tasks per day.",
+        "Consider using noise-cancelling headphones or white noise during Pomodoros to improve focus.",
+        "Hydration improves cognitive function - keep water nearby during your work sessions.",
+        "For creative tasks, sometimes a longer Pomodoro (40-60 minutes) works better than the standard 25.",
+        "Track your completed Pomodoros to visualize your productivity trends over time.",
+        "The Rust crab says: sometimes your most productive Pomodoro isn't the one where you write the most code!",
+    ],
+    work_done_title: Some("{Unit} completed!"),
+    session_done_title: Some("Session {index}/{total} complete!"),
+    work_done_body: Some("{emoji} You completed a {minutes} minute {unit} for: {task}"),
+    break_done_title: Some("Break ended!"),
+    break_done_body: Some("{emoji} Your {minutes} minute break has ended"),
+};
+
+const SPANISH: Language = Language {
+    code: "es",
+    start_work: &[
+        "¡Hora de concentrarse! ¡Tú puedes!",
+        "¡Aprovechemos al máximo estos minutos!",
+        "¡El cangrejo de Rust cree en ti!",
+        "Modo de trabajo profundo: ¡activado!",
+        "Tu yo del futuro te agradecerá que te concentres ahora.",
+    ],
+    during_work: &[],
+    end_work: &[
+        "¡Buen trabajo! Tómate un descanso bien merecido.",
+        "¡Pomodoro completado! ¡El 🦀 está orgulloso de ti!",
+        "¡Te has ganado el descanso!",
+        "¡Excelente sesión de concentración!",
+        "¡Progreso hecho! Hora de recargar energías.",
+    ],
+    start_break: &[
+        "¡Hora del descanso! Relaja la mente.",
+        "El 🦀 dice: ¡hora de relajarse!",
+        "¡Refréscate y recarga!",
+        "¡Estírate, hidrátate, respira!",
+        "Los descansos cortos hacen posibles las largas sesiones de código.",
+    ],
+    end_break: &[
+        "¡Se acabó el descanso! ¿Listo para volver?",
+        "¡Hora de volver al trabajo! ¡El 🦀 está listo!",
+        "¡Renovado y listo para seguir!",
+        "¡De vuelta a avanzar!",
+        "¡Sigamos construyendo cosas increíbles!",
+    ],
+    tips: &[
+        "La técnica Pomodoro funciona mejor cuando te comprometes por completo con la tarea durante el trabajo.",
+        "Ten una lista de tareas pequeñas para los descansos cortos y así mantener el impulso.",
+        "La actividad física en los descansos (como estirarse) te da energía para el siguiente Pomodoro.",
+        "Prueba distintas duraciones de Pomodoro: no a todo el mundo le va mejor con 25 minutos.",
+        "Usa los Pomodoros para estimar cuánto tardarás en tareas parecidas.",
+        "La 'regla de tres' sugiere centrarse en completar solo tres tareas principales al día.",
+        "Prueba auriculares con cancelación de ruido o ruido blanco para concentrarte mejor.",
+        "Hidratarse mejora la función cognitiva: ten agua cerca durante tus sesiones.",
+        "Para tareas creativas, a veces un Pomodoro más largo (40-60 minutos) funciona mejor que el de 25.",
+        "Registra tus Pomodoros completados para ver tu evolución con el tiempo.",
+        "El cangrejo de Rust dice: ¡a veces tu Pomodoro más productivo no es en el que más código escribes!",
+    ],
+    work_done_title: Some("¡{Unit} completado!"),
+    session_done_title: Some("¡Sesión {index}/{total} completada!"),
+    work_done_body: Some("{emoji} Completaste un {unit} de {minutes} minutos para: {task}"),
+    break_done_title: Some("¡Descanso terminado!"),
+    break_done_body: Some("{emoji} Tu descanso de {minutes} minutos ha terminado"),
+};
+
+/// Every supported language; add a new `Language` here to support it
+pub const LANGUAGES: &[&Language] = &[&ENGLISH, &SPANISH];
+
+/// Look up a language by its code, warning and falling back to English if unknown
+pub fn language(code: &str) -> &'static Language {
+    match LANGUAGES.iter().find(|lang| lang.code.eq_ignore_ascii_case(code)) {
+        Some(lang) => lang,
+        None => {
+            let codes: Vec<&str> = LANGUAGES.iter().map(|lang| lang.code).collect();
+            eprintln!("⚠️ Unknown language {:?}, using English (available: {})", code, codes.join(", "));
+            &ENGLISH
+        }
+    }
+}
+
+impl Language {
+    /// Pick a message list, falling back to English when this language has none
+    pub fn list(&self, field: fn(&Language) -> &'static [&'static str]) -> &'static [&'static str] {
+        let messages = field(self);
+        if messages.is_empty() { field(&ENGLISH) } else { messages }
+    }
+
+    /// Pick a template, falling back to English when this language has none
+    pub fn text(&self, field: fn(&Language) -> Option<&'static str>) -> &'static str {
+        field(self).or(field(&ENGLISH)).unwrap_or("")
+    }
+}
+
+/// What a work session is called, e.g. "pomodoro" or "sprint"
+pub struct UnitLabel {
+    singular: String,
+    plural: String,
+}
+
+impl UnitLabel {
+    /// A label whose plural is given, or made by adding an "s"
+    pub fn new(singular: &str, plural: Option<&str>) -> UnitLabel {
+        UnitLabel {
+            singular: singular.to_string(),
+            plural: plural.map_or_else(|| format!("{}s", singular), str::to_string),
+        }
+    }
+
+    /// The singular or plural form, whichever suits `count`
+    pub fn count(&self, count: u64) -> &str {
+        if count == 1 { &self.singular } else { &self.plural }
+    }
+
+    /// The plural form, e.g. "pomodoros"
+    pub fn plural(&self) -> &str {
+        &self.plural
+    }
+
+    /// The singular form with its first letter capitalized, for headers and titles
+    pub fn title(&self) -> String {
+        capitalize(&self.singular)
+    }
+
+    /// The plural form with its first letter capitalized
+    pub fn plural_title(&self) -> String {
+        capitalize(&self.plural)
+    }
+}
+
+impl Default for UnitLabel {
+    fn default() -> UnitLabel {
+        UnitLabel::new("pomodoro", None)
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Replace `{name}` placeholders in a template
+pub fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    values.iter().fold(template.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
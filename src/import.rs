@@ -0,0 +1,260 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use clap::ValueEnum;
+use colored::*;
+use crate::history::{self, Imported, LogFormat};
+
+/// File formats `import` understands
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum ImportSource {
+    /// Comma-separated values with a header row: date, start, duration (minutes) and task
+    Csv,
+}
+
+/// The columns read from a CSV export, in the order they're looked up
+const COLUMNS: [&str; 4] = ["date", "start", "duration", "task"];
+
+/// Date formats tried for the `date` column
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%d.%m.%Y"];
+
+/// Time formats tried for the `start` column
+const TIME_FORMATS: &[&str] = &["%H:%M:%S", "%H:%M", "%I:%M %p"];
+
+/// Import sessions from `file` into the daily logs, reporting what was added
+pub fn run_import(source: ImportSource, file: &Path, mapping: Option<&str>, format: LogFormat) {
+    let result = match source {
+        ImportSource::Csv => read_csv(file, mapping),
+    };
+
+    let (sessions, skipped) = match result {
+        Ok(read) => read,
+        Err(e) => {
+            println!("❌ Failed to import {:?}: {}", file, e);
+            return;
+        }
+    };
+
+    // Sessions are logged on the day they ended, like live ones
+    let mut by_day: BTreeMap<NaiveDate, Vec<Imported>> = BTreeMap::new();
+    for session in sessions {
+        let ended = session.started + chrono::Duration::minutes(session.minutes as i64);
+        by_day.entry(ended.date_naive()).or_default().push(session);
+    }
+
+    let total: usize = by_day.values().map(Vec::len).sum();
+    let mut added = 0;
+    for (date, sessions) in &by_day {
+        match history::import_completed(*date, sessions, format) {
+            Ok(count) => added += count,
+            Err(e) => println!("❌ Failed to write sessions for {}: {}", date, e),
+        }
+    }
+
+    println!("📥 Imported {} of {} sessions from {:?}",
+             added.to_string().bright_green(),
+             total,
+             file);
+    if added < total {
+        println!("{}", format!("{} were already in your history.", total - added).dimmed());
+    }
+    if skipped > 0 {
+        println!("{}", format!("{} rows couldn't be read and were skipped.", skipped).yellow());
+    }
+}
+
+/// Read sessions from a CSV file, returning them with the number of unreadable rows
+fn read_csv(file: &Path, mapping: Option<&str>) -> Result<(Vec<Imported>, usize), String> {
+    let contents = fs::read_to_string(file).map_err(|e| e.to_string())?;
+    parse_csv(&contents, mapping)
+}
+
+/// Read sessions from CSV text, returning them with the number of unreadable rows
+fn parse_csv(contents: &str, mapping: Option<&str>) -> Result<(Vec<Imported>, usize), String> {
+    let mut rows = split_rows(contents)?.into_iter();
+
+    let header = rows.next().ok_or("the file is empty")?;
+    let names = column_names(mapping)?;
+
+    // Position of each of COLUMNS in the file
+    let mut positions = [0; 4];
+    for (position, name) in positions.iter_mut().zip(&names) {
+        *position = header.iter()
+            .position(|field| field.trim().eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("no `{}` column (found: {}); use --mapping to name it", name, header.join(", ")))?;
+    }
+
+    let mut sessions = Vec::new();
+    let mut skipped = 0;
+    for row in rows {
+        let fields: Vec<&str> = positions.iter()
+            .map(|&position| row.get(position).map_or("", |field| field.trim()))
+            .collect();
+
+        match parse_session(fields[0], fields[1], fields[2], fields[3]) {
+            Some(session) => sessions.push(session),
+            None => skipped += 1,
+        }
+    }
+
+    Ok((sessions, skipped))
+}
+
+/// Header names for COLUMNS, with `--mapping` entries like `task=Description` replacing the defaults
+fn column_names(mapping: Option<&str>) -> Result<Vec<String>, String> {
+    let mut names: Vec<String> = COLUMNS.iter().map(|column| column.to_string()).collect();
+
+    for pair in mapping.unwrap_or_default().split(',').filter(|pair| !pair.trim().is_empty()) {
+        let (column, name) = pair.split_once('=')
+            .ok_or_else(|| format!("mapping entry `{}` should look like `task=Description`", pair.trim()))?;
+
+        let index = COLUMNS.iter()
+            .position(|known| known.eq_ignore_ascii_case(column.trim()))
+            .ok_or_else(|| format!("unknown column `{}` in mapping (expected one of: {})", column.trim(), COLUMNS.join(", ")))?;
+        names[index] = name.trim().to_string();
+    }
+
+    Ok(names)
+}
+
+/// Build a session from one row's fields, or `None` if any can't be read
+fn parse_session(date: &str, start: &str, duration: &str, task: &str) -> Option<Imported> {
+    let date = DATE_FORMATS.iter().find_map(|format| NaiveDate::parse_from_str(date, format).ok())?;
+    let time = TIME_FORMATS.iter().find_map(|format| NaiveTime::parse_from_str(start, format).ok())?;
+    let minutes = duration.trim_end_matches("min").trim_end_matches('m').trim().parse().ok()?;
+
+    // Ambiguous times around a DST change take the earlier one
+    let started = Local.from_local_datetime(&NaiveDateTime::new(date, time)).earliest()?;
+    // One line per session in the log, so line breaks from a quoted field become spaces
+    let task = task.split_whitespace().collect::<Vec<_>>().join(" ");
+    let task = if task.is_empty() { "no description".to_string() } else { task };
+
+    Some(Imported { started, task, minutes })
+}
+
+/// Split CSV text into rows of fields, honoring quotes and `""` escapes.
+/// A quoted field may run over several lines; blank lines are skipped.
+fn split_rows(contents: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line_no = 1;
+    let mut row_start = 1;
+    let mut chars = contents.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            },
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => (),
+            '\n' if !quoted => {
+                fields.push(std::mem::take(&mut field));
+                let row = std::mem::take(&mut fields);
+                if row.iter().any(|field| !field.trim().is_empty()) {
+                    rows.push(row);
+                }
+                line_no += 1;
+                row_start = line_no;
+            },
+            c => {
+                if c == '\n' {
+                    line_no += 1;
+                }
+                field.push(c);
+            },
+        }
+    }
+
+    if quoted {
+        return Err(format!("the quote opened on line {} is never closed", row_start));
+    }
+    fields.push(field);
+    if fields.iter().any(|field| !field.trim().is_empty()) {
+        rows.push(fields);
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(row: &[&str]) -> Vec<String> {
+        row.iter().map(|field| field.to_string()).collect()
+    }
+
+    #[test]
+    fn quoted_commas_stay_in_the_field() {
+        let rows = split_rows("date,task\n2024-03-01,\"Review PRs, then lunch\"\n").unwrap();
+        assert_eq!(rows, [fields(&["date", "task"]), fields(&["2024-03-01", "Review PRs, then lunch"])]);
+    }
+
+    #[test]
+    fn doubled_quotes_are_one_quote() {
+        let rows = split_rows("\"Read \"\"Dune\"\"\",\"\"\"\"\n").unwrap();
+        assert_eq!(rows, [fields(&["Read \"Dune\"", "\""])]);
+    }
+
+    #[test]
+    fn quoted_fields_can_span_lines() {
+        let rows = split_rows("a,\"two\r\nlines\"\r\n\r\nb,c").unwrap();
+        assert_eq!(rows, [fields(&["a", "two\r\nlines"]), fields(&["b", "c"])]);
+    }
+
+    #[test]
+    fn an_unclosed_quote_is_an_error() {
+        let err = split_rows("date,task\n2024-03-01,\"oops\n2024-03-02,fine\n").unwrap_err();
+        assert!(err.contains("line 2"), "{}", err);
+    }
+
+    #[test]
+    fn sessions_are_read_by_header_name() {
+        let csv = "Task,Duration,Start,Date\n\"Write\ntests\",25m,09:30,2024-03-01\n,50 min,2:00 PM,01.03.2024\n";
+        let (sessions, skipped) = parse_csv(csv, None).unwrap();
+
+        assert_eq!(skipped, 0);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].task, "Write tests");
+        assert_eq!(sessions[0].minutes, 25);
+        assert_eq!(sessions[0].started.naive_local().to_string(), "2024-03-01 09:30:00");
+        assert_eq!(sessions[1].task, "no description");
+        assert_eq!(sessions[1].minutes, 50);
+        assert_eq!(sessions[1].started.naive_local().to_string(), "2024-03-01 14:00:00");
+    }
+
+    #[test]
+    fn mapping_renames_columns() {
+        let csv = "Day,From,Minutes,Description\n2024-03-01,09:30,25,Deep work\n";
+        let (sessions, _) = parse_csv(csv, Some("date=Day, start=From,duration=Minutes,task=Description")).unwrap();
+        assert_eq!(sessions[0].task, "Deep work");
+
+        assert!(parse_csv(csv, None).unwrap_err().contains("no `date` column"));
+        assert!(column_names(Some("task")).unwrap_err().contains("should look like"));
+        assert!(column_names(Some("project=Client")).unwrap_err().contains("unknown column `project`"));
+    }
+
+    #[test]
+    fn bad_rows_are_skipped_and_counted() {
+        let csv = "date,start,duration,task\n\
+                   2024-03-01,09:30,25,Good\n\
+                   yesterday,09:30,25,Bad date\n\
+                   2024-03-01,half past nine,25,Bad time\n\
+                   2024-03-01,09:30,a while,Bad duration\n\
+                   2024-03-01\n";
+        let (sessions, skipped) = parse_csv(csv, None).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(skipped, 4);
+    }
+
+    #[test]
+    fn an_empty_file_is_an_error() {
+        assert!(parse_csv("\n\n", None).is_err());
+    }
+}
@@ -0,0 +1,52 @@
+use chrono::{Datelike, Local, Months, NaiveDate};
+use colored::*;
+use crate::history::{self, COMPLETED_DIR};
+
+/// Print every billable session logged for `client` (ignoring case) in the month
+/// starting on `month`, this month if `None`, with the hours and the amount due
+pub fn show_timesheet(client: &str, month: Option<NaiveDate>) {
+    let first = month.unwrap_or_else(|| Local::now().date_naive().with_day(1).unwrap());
+    let next = first + Months::new(1);
+    let title = first.format("%B %Y").to_string();
+
+    // The name as it was logged, rather than as typed
+    let mut name = client.to_string();
+    let mut rows = Vec::new();
+    for date in first.iter_days().take_while(|date| *date < next) {
+        for entry in history::read_day(COMPLETED_DIR, date) {
+            let Some(billing) = &entry.billing else {
+                continue;
+            };
+            if billing.client.eq_ignore_ascii_case(client) {
+                name = billing.client.clone();
+                let minutes = entry.minutes.unwrap_or(0);
+                rows.push((date, entry.task.clone(), minutes, billing.rate));
+            }
+        }
+    }
+
+    if rows.is_empty() {
+        println!("\n🧾 {}\n", format!("No billable sessions for {:?} in {}.", client, title).dimmed());
+        return;
+    }
+
+    println!("\n🧾 {} {} {}", "Timesheet for".bright_yellow(), name.bright_cyan(), format!("({})", title).dimmed());
+    println!("{}", format!("   {:<10}  {:>7}  {:>8}  {:>9}  {}", "Date", "Minutes", "Rate", "Amount", "Task").dimmed());
+
+    let mut total_minutes = 0;
+    let mut total_amount = 0.0;
+    for (date, task, minutes, rate) in &rows {
+        let amount = *minutes as f64 * rate / 60.0;
+        total_minutes += minutes;
+        total_amount += amount;
+        println!("   {:<10}  {:>7}  {:>8.2}  {:>9.2}  {}", date.format("%Y-%m-%d"), minutes, rate, amount, task);
+    }
+
+    println!("   {} {} over {} {}, {} {}\n",
+             "Total:".bright_yellow(),
+             format!("{}h {:02}m", total_minutes / 60, total_minutes % 60).bright_green(),
+             rows.len(),
+             if rows.len() == 1 { "session" } else { "sessions" },
+             "amount due".bright_yellow(),
+             format!("{:.2}", total_amount).bright_green());
+}
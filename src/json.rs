@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+
+/// Quote a string as JSON
+pub fn string(s: &str) -> String {
+    let mut out = String::from("\"");
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+/// Parse a flat JSON object of strings, numbers, booleans and nulls into
+/// its fields, with strings unquoted and other values as written.
+/// Nulls are left out. Returns `None` for anything else, including nesting.
+pub fn parse_object(text: &str) -> Option<BTreeMap<String, String>> {
+    let mut chars = text.trim().chars().peekable();
+    let mut fields = BTreeMap::new();
+
+    if chars.next()? != '{' {
+        return None;
+    }
+    skip_whitespace(&mut chars);
+    if chars.peek() == Some(&'}') {
+        chars.next();
+        return chars.next().is_none().then_some(fields);
+    }
+
+    loop {
+        skip_whitespace(&mut chars);
+        let key = parse_string(&mut chars)?;
+        skip_whitespace(&mut chars);
+        if chars.next()? != ':' {
+            return None;
+        }
+        skip_whitespace(&mut chars);
+
+        let value = if chars.peek() == Some(&'"') {
+            Some(parse_string(&mut chars)?)
+        } else {
+            let mut raw = String::new();
+            while let Some(&c) = chars.peek() {
+                if c == ',' || c == '}' || c.is_whitespace() {
+                    break;
+                }
+                raw.push(c);
+                chars.next();
+            }
+            match raw.as_str() {
+                "null" => None,
+                "true" | "false" => Some(raw),
+                _ if raw.parse::<f64>().is_ok() => Some(raw),
+                _ => return None,
+            }
+        };
+
+        if let Some(value) = value {
+            fields.insert(key, value);
+        }
+
+        skip_whitespace(&mut chars);
+        match chars.next()? {
+            ',' => continue,
+            '}' => break,
+            _ => return None,
+        }
+    }
+
+    skip_whitespace(&mut chars);
+    chars.next().is_none().then_some(fields)
+}
+
+/// Find the value at a dotted `path` in any JSON document, e.g. `0.quote` for the
+/// `quote` field of the first object in an array. Strings come back unquoted,
+/// numbers and booleans as written; objects, arrays and nulls give `None`.
+pub fn lookup(text: &str, path: &str) -> Option<String> {
+    let mut chars = text.trim().chars().peekable();
+    let mut value = parse_value(&mut chars)?;
+
+    for segment in path.split('.').filter(|segment| !segment.is_empty()) {
+        value = match value {
+            Value::Object(fields) => fields.into_iter().find(|(key, _)| key == segment)?.1,
+            Value::Array(items) => items.into_iter().nth(segment.parse().ok()?)?,
+            _ => return None,
+        };
+    }
+
+    match value {
+        Value::Scalar(scalar) => Some(scalar),
+        _ => None,
+    }
+}
+
+/// A parsed JSON value, with strings unquoted and other scalars as written
+enum Value {
+    Scalar(String),
+    Null,
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+fn parse_value(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<Value> {
+    skip_whitespace(chars);
+    match *chars.peek()? {
+        '"' => parse_string(chars).map(Value::Scalar),
+        '[' => {
+            chars.next();
+            let mut items = Vec::new();
+            skip_whitespace(chars);
+            if chars.peek() == Some(&']') {
+                chars.next();
+                return Some(Value::Array(items));
+            }
+            loop {
+                items.push(parse_value(chars)?);
+                skip_whitespace(chars);
+                match chars.next()? {
+                    ',' => continue,
+                    ']' => return Some(Value::Array(items)),
+                    _ => return None,
+                }
+            }
+        },
+        '{' => {
+            chars.next();
+            let mut fields = Vec::new();
+            skip_whitespace(chars);
+            if chars.peek() == Some(&'}') {
+                chars.next();
+                return Some(Value::Object(fields));
+            }
+            loop {
+                skip_whitespace(chars);
+                let key = parse_string(chars)?;
+                skip_whitespace(chars);
+                if chars.next()? != ':' {
+                    return None;
+                }
+                fields.push((key, parse_value(chars)?));
+                skip_whitespace(chars);
+                match chars.next()? {
+                    ',' => continue,
+                    '}' => return Some(Value::Object(fields)),
+                    _ => return None,
+                }
+            }
+        },
+        _ => {
+            let mut raw = String::new();
+            while let Some(&c) = chars.peek() {
+                if c == ',' || c == '}' || c == ']' || c.is_whitespace() {
+                    break;
+                }
+                raw.push(c);
+                chars.next();
+            }
+            match raw.as_str() {
+                "null" => Some(Value::Null),
+                "true" | "false" => Some(Value::Scalar(raw)),
+                _ if raw.parse::<f64>().is_ok() => Some(Value::Scalar(raw)),
+                _ => None,
+            }
+        },
+    }
+}
+
+fn skip_whitespace(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+/// Parse a quoted string, undoing escapes
+fn parse_string(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<String> {
+    if chars.next()? != '"' {
+        return None;
+    }
+
+    let mut out = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(out),
+            '\\' => match chars.next()? {
+                'n' => out.push('\n'),
+                'r' => out.push('\r'),
+                't' => out.push('\t'),
+                'u' => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    out.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                },
+                c => out.push(c),
+            },
+            c => out.push(c),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(unix)]
+mod imp {
+    use std::sync::Mutex;
+
+    /// Terminal settings to restore, shared with the Ctrl+C handler
+    static ORIGINAL: Mutex<Option<libc::termios>> = Mutex::new(None);
+
+    /// Switch stdin to unbuffered, no-echo mode; returns false if it isn't a terminal
+    pub fn enable() -> bool {
+        unsafe {
+            if libc::isatty(libc::STDIN_FILENO) != 1 {
+                return false;
+            }
+
+            let mut term: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut term) != 0 {
+                return false;
+            }
+
+            let mut original = ORIGINAL.lock().unwrap();
+            if original.is_none() {
+                *original = Some(term);
+            }
+
+            // Keep ISIG so Ctrl+C still interrupts
+            term.c_lflag &= !(libc::ICANON | libc::ECHO);
+            term.c_cc[libc::VMIN] = 0;
+            term.c_cc[libc::VTIME] = 0;
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &term) == 0
+        }
+    }
+
+    /// Restore the terminal settings saved by `enable`
+    pub fn restore() {
+        if let Ok(mut original) = ORIGINAL.lock()
+            && let Some(term) = original.take() {
+            unsafe {
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &term);
+            }
+        }
+    }
+
+    /// Read one pending key press without blocking
+    pub fn poll() -> Option<char> {
+        let mut fds = libc::pollfd { fd: libc::STDIN_FILENO, events: libc::POLLIN, revents: 0 };
+
+        unsafe {
+            if libc::poll(&mut fds, 1, 0) <= 0 {
+                return None;
+            }
+
+            let mut byte = 0u8;
+            if libc::read(libc::STDIN_FILENO, &mut byte as *mut u8 as *mut libc::c_void, 1) == 1 {
+                Some(byte as char)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    pub fn enable() -> bool { false }
+    pub fn restore() {}
+    pub fn poll() -> Option<char> { None }
+}
+
+/// Listens for key presses until dropped, then restores the terminal
+pub struct KeyListener {
+    active: bool,
+}
+
+impl KeyListener {
+    /// Start listening; does nothing when stdin isn't an interactive terminal
+    pub fn new() -> KeyListener {
+        KeyListener { active: imp::enable() }
+    }
+
+    /// Whether key presses can be read (stdin is an interactive terminal)
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Return the next pending key press, if any
+    pub fn poll(&self) -> Option<char> {
+        if self.active { imp::poll() } else { None }
+    }
+}
+
+impl Drop for KeyListener {
+    fn drop(&mut self) {
+        if self.active {
+            imp::restore();
+        }
+    }
+}
+
+/// Restore the terminal if a listener was active (for use before exiting)
+pub fn restore_terminal() {
+    imp::restore();
+}
@@ -1,17 +1,50 @@
-use clap::{Parser, Subcommand};
-use std::io::{self, Write};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::collections::BTreeMap;
+use std::io::{self, IsTerminal, Write};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 use std::process::Command;
-use std::fs::{OpenOptions, create_dir_all};
-use chrono::Local;
+use chrono::{DateTime, Local, NaiveTime, Offset, TimeZone, Timelike};
 use colored::*;
 use rand::seq::SliceRandom;
 use rand::prelude::*;
 use dialoguer::{Confirm, theme::ColorfulTheme};
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
 use std::path::{Path, PathBuf};
 use dirs::home_dir;
+use keys::KeyListener;
+use i18n::Language;
+use status::{CurrentTimer, ProgressNotification, Spinner, StatusFifo};
+use config::Preset;
+
+mod battery;
+mod clipboard;
+mod config;
+mod emoji;
+mod events;
+mod export;
+mod haptic;
+mod history;
+mod i18n;
+mod import;
+mod invoice;
+mod json;
+mod keys;
+mod menubar;
+mod project;
+mod queue;
+mod quote;
+mod report;
+mod sound;
+mod status;
+mod stats;
+#[cfg(feature = "syslog")]
+mod syslog;
+mod team;
+mod tui;
+mod window;
 
 /// Available emojis for different timer states
 #[derive(Clone)]
@@ -32,6 +65,24 @@ struct Motivations {
     end_break: Vec<&'static str>,
 }
 
+/// Turning a mode on or off
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum Toggle {
+    On,
+    Off,
+}
+
+/// Which motivational messages `motivate` picks from
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum MotivationCategory {
+    /// Messages for starting a work session
+    Start,
+    /// Messages shown while working
+    During,
+    /// Messages for finishing a work session
+    End,
+}
+
 /// CLI application for a friendly Pomodoro timer
 #[derive(Parser)]
 #[command(
@@ -43,6 +94,759 @@ struct Motivations {
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// Minimum session length in minutes for it to count toward stats
+    #[arg(long, global = true)]
+    min_count_minutes: Option<u64>,
+
+    /// Show the projected end time rounded to the nearest minute
+    #[arg(long, global = true)]
+    round_eta: bool,
+
+    /// Let you press `w` during a break to skip it and keep working
+    #[arg(long, global = true)]
+    flexible_breaks: bool,
+
+    /// Don't write this run to the task history
+    #[arg(long, global = true)]
+    no_log: bool,
+
+    /// Print each session start, completion, break and interruption as a JSON line on stdout
+    #[arg(long, global = true)]
+    log_json_stdout: bool,
+
+    /// Show a quote fetched from `online_tip_url` instead of a built-in tip, when online
+    #[arg(long, global = true)]
+    online_tip: bool,
+
+    /// Copy the task to the clipboard when a work session completes
+    #[arg(long, global = true)]
+    copy: bool,
+
+    /// Log the title of the window in focus as each work session starts (X11, macOS)
+    #[arg(long, global = true)]
+    track_window: bool,
+
+    /// Show the output of `--on-complete` commands
+    #[arg(long, global = true)]
+    verbose: bool,
+
+    /// Don't record sessions as started, so interrupted ones leave no trace
+    #[arg(long, global = true)]
+    no_interrupt_logging: bool,
+
+    /// Language for messages, tips and notifications (e.g. `es`)
+    #[arg(long, global = true)]
+    lang: Option<String>,
+
+    /// Pause the timer while the battery is below this percentage and not charging
+    #[arg(long, global = true, value_name = "PERCENT")]
+    pause_on_low_battery: Option<u8>,
+
+    /// Whether an alert that fires while another is playing waits its turn or is skipped
+    #[arg(long, global = true, value_enum)]
+    alert_overlap: Option<sound::Overlap>,
+
+    /// Play the completion alert this many times in a row, up to 10
+    #[arg(long, global = true, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    alert_repeat: Option<u32>,
+
+    /// Directory holding `alert.wav` and other sounds, searched first
+    #[arg(long, global = true, value_name = "PATH")]
+    assets_dir: Option<PathBuf>,
+
+    /// Custom sound file to play when a timer completes
+    #[arg(long, global = true, value_name = "PATH")]
+    sound: Option<PathBuf>,
+
+    /// Directory of .wav files to pick the completion sound from at random
+    #[arg(long, global = true, value_name = "PATH")]
+    sound_dir: Option<PathBuf>,
+
+    /// Set of sounds to use for starting and completing timers
+    #[arg(long, global = true, value_enum)]
+    sound_theme: Option<sound::SoundTheme>,
+
+    /// Don't play any sounds
+    #[arg(long, global = true)]
+    no_sound: bool,
+
+    /// Don't show desktop notifications; messages are printed in the terminal instead
+    #[arg(long, global = true)]
+    no_notify: bool,
+
+    /// Send a quiet notification this many seconds before a work session ends
+    #[arg(long, global = true, value_name = "SECONDS")]
+    warn_before: Option<u64>,
+
+    /// Say the time left out loud every this many minutes, e.g. "10 minutes remaining"
+    #[arg(long, global = true, value_name = "MINUTES", value_parser = clap::value_parser!(u64).range(1..))]
+    speak_interval: Option<u64>,
+
+    /// Keep one desktop notification with the session's progress, updated each minute (Linux)
+    #[arg(long, global = true)]
+    progress_notification: bool,
+
+    /// Named pipe that receives the phase and remaining time every second
+    #[arg(long, global = true, value_name = "PATH")]
+    status_fifo: Option<PathBuf>,
+
+    /// Rumble a force-feedback device (e.g. a gamepad) when a timer completes (Linux only)
+    #[arg(long, global = true)]
+    haptic: bool,
+
+    /// Now and then, show how many pomodoros you've completed all-time
+    #[arg(long, global = true)]
+    count_sessions: bool,
+
+    /// Seconds after a work session ends during which `c` cancels logging it
+    #[arg(long, global = true, value_name = "SECONDS")]
+    grace_period: Option<u64>,
+
+    /// In the default loop, exit if "Start another cycle?" goes unanswered this many minutes (0 waits forever)
+    #[arg(long, global = true, value_name = "MINUTES")]
+    idle_exit: Option<u64>,
+
+    /// How new history lines are written
+    #[arg(long, global = true, value_enum)]
+    log_format: Option<history::LogFormat>,
+
+    /// Which day's log a session that runs past midnight goes in
+    #[arg(long, global = true, value_enum)]
+    attribute_by: Option<history::AttributeBy>,
+
+    /// Skip the encouraging messages, keeping only the timer and notifications
+    #[arg(long, global = true)]
+    no_motivation: bool,
+
+    /// Mark 25%, 50% and 75% of each work session with a note and a soft chime (`milestone.wav`)
+    #[arg(long, global = true)]
+    milestones: bool,
+
+    /// Hide the countdown while a timer runs; press space to peek at the time left
+    #[arg(long, global = true)]
+    blind: bool,
+
+    /// Show a random productivity tip before each work session
+    #[arg(long, global = true)]
+    tip_on_start: bool,
+
+    /// Seconds between redraws of the countdown; timing stays exact either way
+    #[arg(long, global = true, value_parser = clap::value_parser!(u64).range(1..))]
+    refresh: Option<u64>,
+
+    /// After each break, ask what you did and keep it in ~/.break_notes
+    #[arg(long, global = true)]
+    log_breaks: bool,
+
+    /// Number of pomodoros you aim to complete each day
+    #[arg(long, global = true, value_parser = clap::value_parser!(u64).range(1..))]
+    daily_goal: Option<u64>,
+
+    /// Late in the day, say how many sessions are left to reach --daily-goal
+    #[arg(long, global = true)]
+    catch_up: bool,
+
+    /// Print a recap of the day when you stop the default loop or a schedule finishes
+    #[arg(long, global = true)]
+    eod_summary: bool,
+
+    /// Greet with the welcome banner (or your own from `banner_file`) before the command runs
+    #[arg(long, global = true)]
+    banner: bool,
+
+    /// Once this many minutes are logged today, suggest resting and ask before each new session
+    #[arg(long, global = true, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    max_daily_minutes: Option<u64>,
+
+    /// In the default loop and `sprint`, wait until this many minutes have passed since the
+    /// last completed session before starting another
+    #[arg(long, global = true, value_name = "MINUTES", value_parser = clap::value_parser!(u64).range(1..))]
+    min_break_gap: Option<u64>,
+
+    /// Decorate messages with emoji; `auto` turns them off in legacy Windows consoles
+    #[arg(long, global = true, value_enum)]
+    emoji: Option<emoji::EmojiMode>,
+
+    /// Keep one emoji for a whole timer instead of a new one each time; give one with `--pin-emoji=🍅`
+    #[arg(long, global = true, value_name = "EMOJI", num_args = 0..=1, require_equals = true, default_missing_value = "")]
+    pin_emoji: Option<String>,
+
+    /// Show timers full-screen with a big clock and today's sessions (p: pause, s: skip, q: quit)
+    #[arg(long, global = true)]
+    tui: bool,
+
+    /// Show a progress bar for the countdown (styled with the `progress_*` config keys)
+    #[arg(long, global = true)]
+    progress_bar: bool,
+
+    /// Use the subject of the latest git commit here as the task when none is given
+    #[arg(long, global = true)]
+    from_git: bool,
+
+    /// Shared file of per-project counts, bumped for tasks tagged `@project`
+    #[arg(long, global = true, value_name = "PATH")]
+    project_file: Option<PathBuf>,
+
+    /// Name for sessions without a task, e.g. "Session {n}" to number them through the day
+    #[arg(long, global = true, value_name = "LABEL")]
+    untitled_task: Option<String>,
+
+    /// Progress ring shown before the time
+    #[arg(long, global = true, value_enum)]
+    spinner: Option<Spinner>,
+
+    /// Also record finished sessions in the system log (syslog or journald)
+    #[cfg(feature = "syslog")]
+    #[arg(long, global = true)]
+    syslog: bool,
+}
+
+/// Runtime settings resolved from CLI flags and the config file
+struct Settings {
+    /// Work sessions shorter than this are not logged as completed
+    min_count_minutes: u64,
+
+    /// Display the projected end time as `HH:MM` rounded to the nearest minute
+    round_eta: bool,
+
+    /// Allow abandoning a break to start working again
+    flexible_breaks: bool,
+
+    /// Skip all history logging
+    no_log: bool,
+
+    /// Command run after each completed work session is logged, set by `start` and `schedule`
+    on_complete: Option<String>,
+
+    /// Client and rate completed work sessions are billed at, set by `start` and `schedule`
+    billing: Option<history::Billing>,
+
+    /// The specific goal for the work session, set by `start`
+    intent: Option<String>,
+
+    /// Let the `on_complete` command's output through
+    verbose: bool,
+
+    /// What to copy to the clipboard when a work session completes, if anything
+    copy_format: Option<String>,
+
+    /// Record the focused window's title with each completed work session
+    track_window: bool,
+
+    /// Where to fetch tips from, if online tips are on
+    online_tip: Option<quote::OnlineTip>,
+
+    /// Record session starts, so interrupted sessions count against the focus score
+    interrupt_logging: bool,
+
+    /// Language for messages, tips and notifications
+    lang: &'static Language,
+
+    /// What work sessions are called in headers, notifications and stats
+    unit: i18n::UnitLabel,
+
+    /// First day of the week for weekly stats and reports
+    week_start: stats::WeekStart,
+
+    /// Battery percentage below which the timer pauses
+    pause_on_low_battery: Option<u8>,
+
+    /// Alert sound options
+    sound: sound::SoundSettings,
+
+    /// Named pipe for status bar integrations
+    status_fifo: Option<PathBuf>,
+
+    /// Show desktop notifications, rather than only printing messages
+    notify: bool,
+
+    /// Seconds before the end of a work session to warn that it's ending, or 0 for no warning
+    warn_before: u64,
+
+    /// Minutes between spoken time checks while a timer runs
+    speak_interval: Option<u64>,
+
+    /// Keep a notification showing the work session's progress, where it can be updated in place
+    progress_notification: bool,
+
+    /// Rumble a force-feedback device on completion
+    haptic: bool,
+
+    /// Announce the all-time session count every `count_sessions_every` sessions and on milestones
+    count_sessions: bool,
+    count_sessions_every: u64,
+
+    /// Seconds to offer cancelling before a finished session is logged
+    grace_period: u64,
+
+    /// How open-ended sessions are rounded to whole minutes in the log
+    log_rounding: history::Rounding,
+
+    /// Text or JSON Lines for new history entries
+    log_format: history::LogFormat,
+
+    /// Whether a completed session is logged on the day it started or the day it ended
+    attribute_by: history::AttributeBy,
+
+    /// Minutes to wait for an answer before the default loop exits; 0 waits forever
+    idle_exit_minutes: u64,
+
+    /// Use emoji in messages
+    emoji: bool,
+
+    /// Whether a running timer sticks to one emoji
+    pin_emoji: emoji::EmojiPin,
+
+    /// Show timers in the full-screen interface
+    tui: bool,
+
+    /// Mark each quarter of a work session
+    milestones: bool,
+
+    /// Hide the remaining time unless asked
+    blind: bool,
+
+    /// Print a tip before each work session
+    tip_on_start: bool,
+
+    /// Seconds between countdown redraws
+    refresh_seconds: u64,
+
+    /// Ask for a note on how each break was spent
+    log_breaks: bool,
+
+    /// Pomodoros to complete each day, if there's a goal
+    daily_goal: Option<u64>,
+
+    /// Hour of the day from which sessions short of the goal get a nudge, if turned on
+    catch_up_hour: Option<u32>,
+
+    /// Recap the day at the end of the default loop or a schedule
+    eod_summary: bool,
+
+    /// Show the welcome banner on startup
+    banner: bool,
+
+    /// ASCII art shown as the banner instead of the built-in one
+    banner_file: Option<PathBuf>,
+
+    /// Minutes of logged work a day after which starting another session needs a yes
+    max_daily_minutes: Option<u64>,
+
+    /// Least rest, in minutes, between a completed session and the next one in a loop
+    min_break_gap: Option<u64>,
+
+    /// Print encouraging messages between sessions
+    motivation: bool,
+
+    /// Style of the countdown progress bar, if it's turned on
+    progress: Option<ProgressStyle>,
+
+    /// Progress ring before the time
+    spinner: Spinner,
+
+    /// Shared endpoint that completed sessions are posted to
+    team: Option<team::TeamSettings>,
+
+    /// Shared file counting completed sessions per `@project`
+    project_file: Option<PathBuf>,
+
+    /// Write finished sessions to the system log
+    #[cfg(feature = "syslog")]
+    syslog: bool,
+
+    /// Default work session length in minutes
+    work_minutes: u64,
+
+    /// Default short break length in minutes
+    short_break_minutes: u64,
+
+    /// Default long break length in minutes
+    long_break_minutes: u64,
+
+    /// Break minutes per minute of work for `suggest-break`
+    break_ratio: f64,
+
+    /// Default number of sessions in a schedule
+    sessions: u32,
+
+    /// Task description used when none is given
+    default_task: Option<String>,
+
+    /// Take the task from the latest git commit when none is given
+    from_git: bool,
+
+    /// Name for sessions without a task, with `{n}` numbering them through the day
+    untitled_task: Option<String>,
+}
+
+impl Settings {
+    /// Merge CLI flags over config values, falling back to defaults
+    fn resolve(cli: &Cli, config: &config::Config) -> Settings {
+        Settings {
+            min_count_minutes: cli.min_count_minutes.or(config.min_count_minutes).unwrap_or(1),
+            round_eta: cli.round_eta,
+            flexible_breaks: cli.flexible_breaks,
+            no_log: cli.no_log,
+            on_complete: None,
+            billing: None,
+            intent: None,
+            verbose: cli.verbose,
+            track_window: cli.track_window || config.track_window.unwrap_or(false),
+            copy_format: (cli.copy || config.copy.unwrap_or(false))
+                .then(|| config.copy_format.clone().unwrap_or_else(|| "{task}".to_string())),
+            online_tip: (cli.online_tip || config.online_tip.unwrap_or(false)).then(|| quote::OnlineTip {
+                url: config.online_tip_url.clone().unwrap_or_else(|| quote::DEFAULT_URL.to_string()),
+                field: config.online_tip_field.clone().unwrap_or_else(|| quote::DEFAULT_FIELD.to_string()),
+            }),
+            interrupt_logging: !(cli.no_interrupt_logging || config.no_interrupt_logging.unwrap_or(false)),
+            lang: i18n::language(cli.lang.as_deref().or(config.lang.as_deref()).unwrap_or("en")),
+            unit: config.unit_label.as_deref()
+                .map(|label| i18n::UnitLabel::new(label, config.unit_label_plural.as_deref()))
+                .unwrap_or_default(),
+            week_start: config.week_start.unwrap_or(stats::WeekStart::Monday),
+            pause_on_low_battery: cli.pause_on_low_battery.or(config.pause_on_low_battery),
+            sound: sound::SoundSettings {
+                enabled: !(cli.no_sound || config.no_sound.unwrap_or(false)),
+                custom: cli.sound.clone().or(config.sound.clone()),
+                sound_dir: existing_dir(cli.sound_dir.clone().or(config.sound_dir.clone()), "--sound-dir"),
+                overlap: cli.alert_overlap.or(config.alert_overlap).unwrap_or(sound::Overlap::Queue),
+                theme: cli.sound_theme.or(config.sound_theme).unwrap_or(sound::SoundTheme::Chime),
+                repeat: cli.alert_repeat.or(config.alert_repeat).unwrap_or(1).min(sound::MAX_ALERT_REPEAT),
+                assets_dir: existing_dir(cli.assets_dir.clone().or(config.assets_dir.clone()), "--assets-dir"),
+            },
+            status_fifo: cli.status_fifo.clone().or(config.status_fifo.clone()),
+            notify: !(cli.no_notify || config.no_notify.unwrap_or(false)),
+            warn_before: cli.warn_before.or(config.warn_before).unwrap_or(0),
+            speak_interval: cli.speak_interval.or(config.speak_interval),
+            progress_notification: cli.progress_notification || config.progress_notification.unwrap_or(false),
+            haptic: cli.haptic || config.haptic.unwrap_or(false),
+            count_sessions: cli.count_sessions || config.count_sessions.unwrap_or(false),
+            count_sessions_every: config.count_sessions_every.unwrap_or(10),
+            team: config.team_endpoint.clone().map(|endpoint| team::TeamSettings {
+                endpoint,
+                user: config.team_user.clone()
+                    .or_else(|| std::env::var("USER").ok())
+                    .or_else(|| std::env::var("USERNAME").ok())
+                    .unwrap_or_else(|| "anonymous".to_string()),
+            }),
+            #[cfg(feature = "syslog")]
+            syslog: cli.syslog || config.syslog.unwrap_or(false),
+            project_file: cli.project_file.clone().or(config.project_file.clone()),
+            grace_period: cli.grace_period.or(config.grace_period).unwrap_or(0),
+            log_rounding: config.log_duration_rounding.unwrap_or(history::Rounding::Down),
+            log_format: cli.log_format.or(config.log_format).unwrap_or(history::LogFormat::Text),
+            attribute_by: cli.attribute_by.or(config.attribute_by).unwrap_or(history::AttributeBy::End),
+            idle_exit_minutes: cli.idle_exit.or(config.idle_exit_minutes).unwrap_or(30),
+            emoji: cli.emoji.or(config.emoji).unwrap_or(emoji::EmojiMode::Auto).enabled(),
+            pin_emoji: match cli.pin_emoji.as_deref() {
+                Some("") => emoji::EmojiPin::PerTimer,
+                Some(emoji) => emoji::EmojiPin::Fixed(emoji.to_string()),
+                None => config.pin_emoji.clone().unwrap_or(emoji::EmojiPin::Off),
+            },
+            tui: cli.tui,
+            milestones: cli.milestones || config.milestones.unwrap_or(false),
+            blind: cli.blind || config.blind.unwrap_or(false),
+            tip_on_start: cli.tip_on_start || config.tip_on_start.unwrap_or(false),
+            refresh_seconds: cli.refresh.or(config.refresh).unwrap_or(1),
+            // Nobody is there to answer when input is piped
+            log_breaks: (cli.log_breaks || config.log_breaks.unwrap_or(false))
+                && !cli.no_log && io::stdin().is_terminal(),
+            daily_goal: cli.daily_goal.or(config.daily_goal),
+            catch_up_hour: (cli.catch_up || config.catch_up.unwrap_or(false))
+                .then(|| config.catch_up_hour.unwrap_or(17)),
+            eod_summary: cli.eod_summary || config.eod_summary.unwrap_or(false),
+            // Setting your own art is opting in
+            banner: cli.banner || config.banner.unwrap_or(false) || config.banner_file.is_some(),
+            banner_file: config.banner_file.clone(),
+            max_daily_minutes: cli.max_daily_minutes.or(config.max_daily_minutes),
+            min_break_gap: cli.min_break_gap.or(config.min_break_gap),
+            // The full-screen view and scripts have no use for chatter
+            motivation: !(cli.no_motivation || config.no_motivation.unwrap_or(false)
+                || cli.tui || !io::stdout().is_terminal()),
+            progress: (cli.progress_bar || config.progress_bar.unwrap_or(false)).then(|| progress_style(config)),
+            spinner: cli.spinner.or(config.spinner).unwrap_or(Spinner::None),
+            work_minutes: config.work.unwrap_or(25),
+            short_break_minutes: config.short_break.unwrap_or(5),
+            long_break_minutes: config.long_break.unwrap_or(15),
+            break_ratio: config.break_ratio.unwrap_or(0.2),
+            sessions: config.sessions.unwrap_or(4),
+            default_task: config.task.clone(),
+            from_git: cli.from_git,
+            untitled_task: cli.untitled_task.clone().or(config.untitled_task.clone()),
+        }
+    }
+
+    /// Pick the task given on the command line, then the last commit with `--from-git`, then the
+    /// configured default, then the `untitled_task` label, then `fallback`. A `{n}` in the label
+    /// is numbered as each session starts.
+    fn task_or(&self, task: &Option<String>, fallback: &str) -> String {
+        task.clone()
+            .or_else(|| self.from_git.then(last_commit_subject).flatten())
+            .or_else(|| self.default_task.clone())
+            .or_else(|| self.untitled_task.clone())
+            .unwrap_or_else(|| fallback.to_string())
+    }
+
+    /// Fill in the `{n}` of the `untitled_task` label as a session starts, so each one
+    /// in a schedule or loop gets the next number. Any other task, like one you typed
+    /// or the configured default, is used as is, even if it contains `{n}`.
+    fn number_untitled(&self, task_desc: &str) -> String {
+        if self.untitled_task.as_deref() == Some(task_desc) {
+            history::next_in_sequence(task_desc)
+        } else {
+            task_desc.to_string()
+        }
+    }
+}
+
+/// Longest commit subject used as a task with `--from-git`
+const GIT_SUBJECT_LIMIT: usize = 60;
+
+/// Subject of the latest commit in the current repository, trimmed and shortened,
+/// or `None` outside a repository (or without git)
+fn last_commit_subject() -> Option<String> {
+    let output = Command::new("git")
+        .args(["log", "-1", "--format=%s"])
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+
+    let subject = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if subject.is_empty() {
+        return None;
+    }
+
+    if subject.chars().count() > GIT_SUBJECT_LIMIT {
+        let cut: String = subject.chars().take(GIT_SUBJECT_LIMIT - 1).collect();
+        Some(format!("{}…", cut.trim_end()))
+    } else {
+        Some(subject)
+    }
+}
+
+/// Progress bar layout when `progress_template` isn't set; `{msg}` holds the usual status line
+const DEFAULT_PROGRESS_TEMPLATE: &str = "{msg} {bar.green/white} {percent:>3}%";
+
+/// Build the progress bar style from the `progress_*` config keys, warning about
+/// and replacing any that can't be used
+fn progress_style(config: &config::Config) -> ProgressStyle {
+    let width = config.progress_width.unwrap_or(30);
+
+    let template = match config.progress_template.as_deref() {
+        Some(template) if template.contains("{msg}") && template.contains("{bar") => template,
+        Some(template) => {
+            eprintln!("⚠️ progress_template {:?} needs both {{msg}} and {{bar}}, using the default", template);
+            DEFAULT_PROGRESS_TEMPLATE
+        },
+        None => DEFAULT_PROGRESS_TEMPLATE,
+    };
+
+    // `progress_width` sizes any bar that doesn't give its own width
+    let template = template.replace("{bar}", &format!("{{bar:{}}}", width))
+        .replace("{bar.", &format!("{{bar:{}.", width));
+
+    let style = ProgressStyle::with_template(&template).unwrap_or_else(|e| {
+        eprintln!("⚠️ Invalid progress_template: {}, using the default", e);
+        ProgressStyle::with_template(&DEFAULT_PROGRESS_TEMPLATE.replace("{bar.", &format!("{{bar:{}.", width)))
+            .expect("default progress template is valid")
+    });
+
+    let chars = match config.progress_chars.as_deref() {
+        None | Some("block") => "█▉▊▋▌▍▎▏ ",
+        Some("ascii") => "=> ",
+        Some(chars) => chars,
+    };
+
+    // indicatif needs at least a "filled" and an "empty" character
+    if chars.chars().count() < 2 {
+        eprintln!("⚠️ progress_chars {:?} needs at least two characters, using the default", chars);
+        return style;
+    }
+
+    style.progress_chars(chars)
+}
+
+/// Keep a configured directory only if it exists, warning about it otherwise
+fn existing_dir(dir: Option<PathBuf>, option: &str) -> Option<PathBuf> {
+    let dir = dir?;
+
+    if dir.is_dir() {
+        Some(dir)
+    } else {
+        eprintln!("⚠️ {} {:?} is not a directory, ignoring it", option, dir);
+        None
+    }
+}
+
+/// Parameters for a sequence of pomodoros
+struct SchedulePlan {
+    sessions: u32,
+    work: u64,
+    short_break: u64,
+    long_break: u64,
+    /// Ask before starting each work session
+    confirm_each: bool,
+    /// Take a long break whenever this many minutes of work have built up,
+    /// rather than only at the end
+    long_break_after: Option<u64>,
+    /// Print when the whole schedule should end before it starts
+    estimate_finish: bool,
+}
+
+impl SchedulePlan {
+    /// The schedule a preset runs, with what it leaves out taken from `settings`
+    fn from_preset(preset: &Preset, settings: &Settings) -> SchedulePlan {
+        SchedulePlan {
+            sessions: preset.sessions.unwrap_or(settings.sessions),
+            work: preset.work.unwrap_or(settings.work_minutes),
+            short_break: preset.short_break.unwrap_or(settings.short_break_minutes),
+            long_break: preset.long_break.unwrap_or(settings.long_break_minutes),
+            confirm_each: preset.confirm_each.unwrap_or(false),
+            long_break_after: preset.long_break_after_minutes,
+            estimate_finish: false,
+        }
+    }
+
+    /// Minutes from the first session to the end of the final long break,
+    /// if every timer runs its full length
+    fn total_minutes(&self) -> u64 {
+        let mut total = 0;
+        let mut worked = 0;
+        for i in 1..=self.sessions {
+            total += self.work;
+            worked += self.work;
+            if i < self.sessions && self.long_break_after.is_some_and(|threshold| worked >= threshold) {
+                total += self.long_break;
+                worked = 0;
+            } else if i < self.sessions {
+                total += self.short_break;
+            } else {
+                total += self.long_break;
+            }
+        }
+        total
+    }
+}
+
+/// How a work session ended
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum WorkEnd {
+    /// Ran its course, logging these minutes (counting time added or taken off); may be 0
+    Completed(u64),
+    /// Skipped with a stop key, so nothing was logged
+    Skipped,
+    /// You chose to stop at the daily cap, so the caller ends its run
+    StopForToday,
+}
+
+impl WorkEnd {
+    /// Minutes worked, 0 unless it completed
+    fn minutes(self) -> u64 {
+        match self {
+            WorkEnd::Completed(minutes) => minutes,
+            WorkEnd::Skipped | WorkEnd::StopForToday => 0,
+        }
+    }
+}
+
+/// How a timer run ended
+enum TimerEnd {
+    /// The full duration elapsed; `seconds` includes any time added or taken off
+    Finished { seconds: u64 },
+    /// The user pressed one of the timer's stop keys
+    Stopped,
+}
+
+/// A timer's countdown, kept by the wall clock rather than by passes through the loop, so time
+/// spent asleep (e.g. a closed laptop lid) still counts and the timer catches up on wake
+struct Countdown<Tz: TimeZone> {
+    /// The whole timer, with any time added or taken off; what a finished session logs
+    total: u64,
+    remaining: u64,
+    last_tick: DateTime<Tz>,
+    /// Milliseconds short of a whole second, carried over to the next tick
+    carry_ms: i64,
+}
+
+impl<Tz: TimeZone> Countdown<Tz> {
+    fn new(seconds: u64, now: DateTime<Tz>) -> Self {
+        Countdown { total: seconds, remaining: seconds, last_tick: now, carry_ms: 0 }
+    }
+
+    /// Count off the time since the last tick, unless `held` (paused).
+    /// A clock set backwards counts as no time passing.
+    fn tick(&mut self, now: DateTime<Tz>, held: bool) {
+        let passed_ms = now.clone().signed_duration_since(&self.last_tick).num_milliseconds().max(0);
+        self.last_tick = now;
+        if !held {
+            self.carry_ms += passed_ms;
+            self.remaining = self.remaining.saturating_sub((self.carry_ms / 1000) as u64);
+            self.carry_ms %= 1000;
+        }
+    }
+
+    fn add(&mut self, seconds: u64) {
+        self.remaining += seconds;
+        self.total += seconds;
+    }
+
+    /// Take off up to `seconds`, never cutting into the second that's already running
+    fn cut(&mut self, seconds: u64) {
+        let cut = seconds.min(self.remaining.saturating_sub(1));
+        self.remaining -= cut;
+        self.total -= cut;
+    }
+}
+
+/// Keys that add or take off `ADJUST_SECONDS` while a timer runs
+const ADD_TIME_KEY: char = '+';
+const REMOVE_TIME_KEY: char = '-';
+const ADJUST_SECONDS: u64 = 5 * 60;
+
+/// Seconds a milestone stays on the full-screen view
+const MILESTONE_NOTE_SECONDS: u64 = 10;
+
+/// Key that shows the remaining time for `PEEK_SECONDS` in `--blind` mode
+const PEEK_KEY: char = ' ';
+const PEEK_SECONDS: u64 = 3;
+
+/// Shortest break `suggest-break` will recommend, in minutes
+const MIN_SUGGESTED_BREAK: u64 = 2;
+
+/// Key that abandons a break when `--flexible-breaks` is set
+const KEEP_WORKING_KEY: char = 'w';
+
+/// When set, Ctrl+C raises `INTERRUPTED` instead of exiting right away
+static CATCH_INTERRUPT: AtomicBool = AtomicBool::new(false);
+
+/// Set by the Ctrl+C handler while `CATCH_INTERRUPT` is on
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// The work session being timed, so quitting midway can log how far it got
+static RUNNING_WORK: Mutex<Option<RunningWork>> = Mutex::new(None);
+
+struct RunningWork {
+    task: String,
+    started: DateTime<Local>,
+    planned: u64,
+    rounding: history::Rounding,
+    format: history::LogFormat,
+}
+
+/// Log the running work session, if any, as interrupted
+fn log_interrupted_work() {
+    let Some(work) = RUNNING_WORK.lock().ok().and_then(|mut running| running.take()) else {
+        return;
+    };
+
+    let seconds = Local::now().signed_duration_since(work.started).num_seconds().max(0) as u64;
+    let minutes = work.rounding.minutes(seconds);
+    if minutes > 0 {
+        history::log_interrupted_task(&work.task, minutes, work.planned, work.format);
+    }
 }
 
 /// Available commands for the Pomodoro timer
@@ -50,196 +854,742 @@ struct Cli {
 enum Commands {
     /// Start a Pomodoro work interval (25 minutes by default)
     Start {
-        /// Custom duration in minutes
-        #[arg(short, long, default_value_t = 25)]
-        duration: u64,
+        /// Custom duration in minutes [default: 25]
+        #[arg(short, long)]
+        duration: Option<u64>,
 
         /// Task description
         #[arg(short, long)]
         task: Option<String>,
+
+        /// Follow the session with a break of this many minutes
+        #[arg(long, value_name = "MINUTES")]
+        then_break: Option<u64>,
+
+        /// Wait until this time of day before starting; a time already past means tomorrow
+        #[arg(long, value_name = "HH:MM", value_parser = parse_clock_time)]
+        start_at: Option<NaiveTime>,
+
+        /// Shell command to run once the session is completed and logged; `{task}` and `{minutes}` are filled in
+        #[arg(long, value_name = "CMD")]
+        on_complete: Option<String>,
+
+        /// The specific goal for this session (the task is what you work on, this is what
+        /// you mean to get done); shown above the timer, and you're asked at the end if you met it
+        #[arg(long, value_name = "TEXT")]
+        intent: Option<String>,
+
+        #[command(flatten)]
+        billing: BillingArgs,
+    },
+
+    /// Work then take a short break straight away, no questions asked
+    Sprint {
+        /// Task description [default: $POMODORO_TASK, or "Focus sprint"]
+        task: Option<String>,
     },
 
     /// Start a break (5 minutes by default)
     Break {
-        /// Break duration in minutes
-        #[arg(short, long, default_value_t = 5)]
-        duration: u64,
+        /// Break duration in minutes [default: 5, or 15 with --long]
+        #[arg(short, long)]
+        duration: Option<u64>,
 
         /// Whether this is a long break
         #[arg(short, long)]
         long: bool,
     },
 
+    /// Suggest a break length to pair with a work session of a given length
+    SuggestBreak {
+        /// Work session length in minutes [default: 25]
+        #[arg(short, long, value_parser = clap::value_parser!(u64).range(1..))]
+        work: Option<u64>,
+
+        /// Break minutes per minute of work [default: 0.2]
+        #[arg(short, long, value_parser = parse_ratio)]
+        ratio: Option<f64>,
+
+        /// Take the suggested break straight away
+        #[arg(long)]
+        start: bool,
+    },
+
     /// Schedule a sequence of pomodoros
     Schedule {
-        /// Number of pomodoro sessions
-        #[arg(short, long, default_value_t = 4)]
-        sessions: u32,
+        /// Number of pomodoro sessions [default: 4]
+        #[arg(short, long)]
+        sessions: Option<u32>,
 
-        /// Work duration in minutes
-        #[arg(short, long, default_value_t = 25)]
-        work: u64,
+        /// Work duration in minutes [default: 25]
+        #[arg(short, long)]
+        work: Option<u64>,
 
-        /// Short break duration in minutes
-        #[arg(short = 'b', long, default_value_t = 5)]
-        short_break: u64,
+        /// Short break duration in minutes [default: 5]
+        #[arg(short = 'b', long)]
+        short_break: Option<u64>,
 
-        /// Long break duration in minutes
-        #[arg(short, long, default_value_t = 15)]
-        long_break: u64,
+        /// Long break duration in minutes [default: 15]
+        #[arg(short, long)]
+        long_break: Option<u64>,
 
         /// Task description
         #[arg(short, long)]
         task: Option<String>,
+
+        /// Ask for confirmation before each work session
+        #[arg(long)]
+        confirm_each: bool,
+
+        /// Take a long break each time this many minutes of work have built up, not just at the end
+        #[arg(long, value_name = "MINUTES", value_parser = clap::value_parser!(u64).range(1..))]
+        long_break_after_minutes: Option<u64>,
+
+        /// Print the time the schedule should finish before it starts
+        #[arg(long)]
+        estimate_finish: bool,
+
+        /// Shell command to run as each session is completed and logged; `{task}` and `{minutes}` are filled in
+        #[arg(long, value_name = "CMD")]
+        on_complete: Option<String>,
+
+        #[command(flatten)]
+        billing: BillingArgs,
+    },
+
+    /// Run a schedule saved as a `[presets.<name>]` section in the config
+    Run {
+        /// Name of the preset
+        preset: String,
+    },
+
+    /// List the presets in the config, or show one in full
+    Presets {
+        #[command(subcommand)]
+        action: PresetsAction,
+    },
+
+    /// Line up tasks, then work through them one pomodoro and break each
+    Queue {
+        #[command(subcommand)]
+        action: QueueAction,
     },
 
     /// Install the binary to your PATH
-    Install,
+    Install {
+        #[command(flatten)]
+        options: InstallArgs,
+    },
+
+    /// Walk through first-time setup: durations, sounds, notifications and installing
+    Setup,
+
+    /// Remove the installed binary and the PATH line install added
+    Uninstall {
+        /// Answer yes to the confirmation prompt
+        #[arg(long, short = 'y')]
+        yes: bool,
+
+        /// Directory the binary was installed into, if not ~/.local/bin
+        #[arg(long)]
+        bin_dir: Option<PathBuf>,
+
+        /// Also remove the assets directory next to the binary
+        #[arg(long)]
+        assets: bool,
+    },
 
     /// Get a random productivity tip
     Tip,
-}
 
-/// Initialize emoji collections
-fn init_emojis() -> Emojis {
-    Emojis {
-        work: vec!["🍅", "💻", "📝", "🔨", "⚙️", "🧠", "🦀", "🚀", "⏳", "🔍"],
-        break_short: vec!["☕", "🍵", "🧘", "🌱", "🌞", "💆", "🦀", "🎵", "🍃", "🌈"],
-        break_long: vec!["🌴", "🏖️", "🎮", "📚", "🍦", "🦀", "🎨", "🌿", "🧁", "🎬"],
-        success: vec!["✅", "🎉", "🏆", "💯", "🌟", "🙌", "🦀", "🥳", "💪", "🌺"],
-        rust: vec!["🦀"],
-    }
-}
+    /// Get a random motivational message
+    Motivate {
+        /// Kind of message to pick from
+        #[arg(short, long, value_enum, default_value_t = MotivationCategory::Start)]
+        category: MotivationCategory,
+    },
 
-/// Initialize motivational messages
-fn init_motivations() -> Motivations {
-    Motivations {
-        start_work: vec![
-            "Time to focus! You've got this!",
-            "Let's make the most of these minutes!",
-            "The Rust crab believes in you!",
-            "Deep work mode: engaged!",
-            "Your future self will thank you for focusing now.",
-        ],
-        during_work: vec![
-            "....",
-            "....",
-            "....",
-            "....",
-            "....",
-        ],
-        end_work: vec![
-            "Great job! Take a well-deserved break.",
-            "Pomodoro complete! The 🦀 is proud of you!",
-            "You've earned your rest!",
-            "Excellent focus session!",
-            "Progress made! Time to recharge.",
-        ],
-        start_break: vec![
-            "Break time! Rest your mind.",
-            "The 🦀 says: time to relax!",
-            "Refresh and recharge!",
-            "Stretch, hydrate, breathe!",
-            "Short breaks make long coding sessions possible.",
-        ],
-        end_break: vec![
-            "Break's over! Ready to dive back in?",
-            "Time to get back to it! The 🦀 is ready!",
-            "Refreshed and ready to go!",
-            "Back to making progress!",
-            "Let's continue building amazing things!",
-        ],
-    }
-}
+    /// Show your focus score (completed vs. started sessions)
+    Stats {
+        /// Show a calendar heatmap of the last year instead
+        #[arg(long)]
+        heatmap: bool,
 
-/// Get a random element from a string vector
-fn random_from<'a>(vec: &'a [&'static str]) -> &'a str {
-    vec.choose(&mut thread_rng()).unwrap_or(&"")
-}
+        /// Also count interrupted sessions as fractions, by how much of them ran
+        #[arg(long)]
+        fractional: bool,
+    },
 
-fn main() {
-    let cli = Cli::parse();
+    /// Show completed sessions per week as a sparkline, with week-over-week changes
+    Trends {
+        /// How many weeks back to look
+        #[arg(short, long, default_value_t = 12)]
+        weeks: usize,
+    },
 
-    // Initialize emojis and motivational messages
-    let emojis = init_emojis();
-    let motivations = init_motivations();
+    /// Show the total time ever spent on a task, across all days
+    TaskTime {
+        /// Task to look for; any task containing it matches, ignoring case
+        task: String,
 
-    // Set up Ctrl+C handler for clean termination
-    let success_emojis = emojis.success.clone();
-    let rust_emojis = emojis.rust.clone();
+        /// Only count tasks named exactly this (still ignoring case)
+        #[arg(long)]
+        exact: bool,
+    },
 
-    ctrlc::set_handler(move || {
-        println!();
-        std::process::exit(0);
-    }).expect("Error setting Ctrl+C handler");
+    /// Count up from zero until you press a key, then log the elapsed time
+    Stopwatch {
+        /// Task description
+        #[arg(short, long)]
+        task: Option<String>,
+    },
 
-    // Display welcome message on first run
-    // print_welcome_message(&emojis);
+    /// Randomly pick one of several tasks and start a pomodoro on it
+    Pick {
+        /// Tasks to choose from
+        tasks: Vec<String>,
+
+        /// Read tasks from a file, one per line
+        #[arg(short, long, value_name = "FILE")]
+        from: Option<PathBuf>,
+
+        /// Custom duration in minutes [default: 25]
+        #[arg(short, long)]
+        duration: Option<u64>,
+    },
+
+    /// Write this week's summary as Markdown under the data directory
+    Report {
+        /// Also email the report using `sendmail` and the `email_to` config key
+        #[arg(long)]
+        email: bool,
+    },
+
+    /// Remove the most recently logged session from today's log
+    Undo,
+
+    /// Print a day's sessions, e.g. as a time-blocked agenda
+    Export {
+        /// Day to export, as YYYY-MM-DD [default: today]
+        #[arg(short, long, value_name = "DATE")]
+        day: Option<chrono::NaiveDate>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = export::ExportFormat::Agenda)]
+        format: export::ExportFormat,
+    },
+
+    /// Open a day's log of completed sessions in $EDITOR, for fixing it by hand
+    OpenLog {
+        /// Day to open, as YYYY-MM-DD [default: today]
+        #[arg(short, long, value_name = "DATE")]
+        day: Option<chrono::NaiveDate>,
+    },
+
+    /// Add sessions from another pomodoro app's export to your history
+    Import {
+        /// Format of the file
+        #[arg(long, value_enum, default_value_t = import::ImportSource::Csv)]
+        from: import::ImportSource,
+
+        /// File to import
+        #[arg(long)]
+        file: PathBuf,
+
+        /// Column names when they differ from date, start, duration and task,
+        /// e.g. `start=Began,task=Description`
+        #[arg(long)]
+        mapping: Option<String>,
+    },
+
+    /// Silence all sounds and notifications, including for timers already running
+    Present {
+        #[arg(value_enum)]
+        state: Toggle,
+    },
+
+    /// Print the seconds left on the running timer, for scripts; exits with 1 if none is running
+    Remaining,
+
+    /// Print the running timer as a SwiftBar or xbar plugin, for the macOS menu bar
+    Menubar {
+        /// Keep printing an update every second, for a streamable SwiftBar plugin
+        #[arg(long)]
+        stream: bool,
+    },
+
+    /// Carry on with a work session cut short by a crash or reboot
+    Resume,
+
+    /// Play the alert sound once to check that audio works
+    PreviewSound,
+
+    /// Print the alert sound's sample rate, channels and duration
+    SoundInfo,
+
+    /// Print a timesheet of billable sessions for one client and month, with the amount due
+    Invoice {
+        /// Client name, as given to --client (any case)
+        #[arg(short, long)]
+        client: String,
+
+        /// Month to bill, as YYYY-MM [default: this month]
+        #[arg(short, long, value_name = "YYYY-MM", value_parser = parse_month)]
+        month: Option<chrono::NaiveDate>,
+    },
+
+    /// Nag you with a break reminder every few minutes until Ctrl+C; nothing is logged
+    Remind {
+        /// Minutes between reminders [default: 25]
+        #[arg(short, long, value_parser = clap::value_parser!(u64).range(1..))]
+        every: Option<u64>,
+
+        /// Custom reminder text
+        #[arg(short, long)]
+        message: Option<String>,
+    },
+}
+
+/// What `presets` does
+#[derive(Subcommand)]
+enum PresetsAction {
+    /// Each preset's name with a one-line summary
+    List,
+    /// Every setting of one preset, and where it comes from
+    Show {
+        /// Name of the preset
+        name: String,
+    },
+}
+
+/// What `queue` does
+#[derive(Subcommand)]
+enum QueueAction {
+    /// Put a task at the end of the queue
+    Add {
+        /// Task description
+        task: String,
+    },
+    /// Show the queued tasks in order
+    List,
+    /// Work on each queued task in turn until the queue is empty
+    Run,
+}
+
+/// Options for logging work sessions as billable to a client
+#[derive(Args)]
+struct BillingArgs {
+    /// Log each completed session as billable, with --client and --rate
+    #[arg(long, requires_all = ["client", "rate"])]
+    billable: bool,
+
+    /// Client the sessions are billed to
+    #[arg(long, requires = "billable")]
+    client: Option<String>,
+
+    /// Hourly rate charged for the sessions
+    #[arg(long, requires = "billable", value_parser = parse_rate)]
+    rate: Option<f64>,
+}
+
+impl BillingArgs {
+    fn billing(&self) -> Option<history::Billing> {
+        let client = self.client.clone().filter(|_| self.billable)?;
+        Some(history::Billing { client, rate: self.rate? })
+    }
+}
+
+/// Options for installing the binary, including the ones that make it scriptable
+#[derive(Args, Default)]
+struct InstallArgs {
+    /// Print every step that would change something, without doing it
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Answer yes to every prompt, for setup scripts and Dockerfiles
+    #[arg(long, short = 'y')]
+    yes: bool,
+
+    /// Directory to install the binary into, instead of ~/.local/bin
+    #[arg(long)]
+    bin_dir: Option<PathBuf>,
+
+    /// Never touch a shell profile; just say what to add to PATH
+    #[arg(long)]
+    no_path_edit: bool,
+}
+
+/// Initialize emoji collections, or plain ASCII markers when emoji are turned off
+fn init_emojis(enabled: bool) -> Emojis {
+    if !enabled {
+        return Emojis {
+            work: vec!["*"],
+            break_short: vec!["~"],
+            break_long: vec!["~"],
+            success: vec!["+"],
+            rust: vec![""],
+        };
+    }
+
+    Emojis {
+        work: vec!["🍅", "💻", "📝", "🔨", "⚙️", "🧠", "🦀", "🚀", "⏳", "🔍"],
+        break_short: vec!["☕", "🍵", "🧘", "🌱", "🌞", "💆", "🦀", "🎵", "🍃", "🌈"],
+        break_long: vec!["🌴", "🏖️", "🎮", "📚", "🍦", "🦀", "🎨", "🌿", "🧁", "🎬"],
+        success: vec!["✅", "🎉", "🏆", "💯", "🌟", "🙌", "🦀", "🥳", "💪", "🌺"],
+        rust: vec!["🦀"],
+    }
+}
+
+/// Initialize motivational messages in the chosen language
+fn init_motivations(lang: &Language) -> Motivations {
+    Motivations {
+        start_work: lang.list(|l| l.start_work).to_vec(),
+        during_work: lang.list(|l| l.during_work).to_vec(),
+        end_work: lang.list(|l| l.end_work).to_vec(),
+        start_break: lang.list(|l| l.start_break).to_vec(),
+        end_break: lang.list(|l| l.end_break).to_vec(),
+    }
+}
+
+/// Get a random element from a string vector
+fn random_from<'a>(vec: &'a [&'static str]) -> &'a str {
+    vec.choose(&mut thread_rng()).unwrap_or(&"")
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    // Answer straight away, without loading config or touching anything
+    if let Some(Commands::Remaining) = cli.command {
+        match status::remaining() {
+            Some(seconds) => println!("{}", seconds),
+            None => std::process::exit(1),
+        }
+        return;
+    }
+    if let Some(Commands::Menubar { stream }) = cli.command {
+        menubar::print(stream);
+        return;
+    }
+    let config = config::load_config();
+    let mut settings = Settings::resolve(&cli, &config);
+    if cli.log_json_stdout {
+        events::enable();
+    }
+
+    // Send anything left over from an earlier offline run
+    if let Some(team) = &settings.team {
+        team::flush_in_background(team);
+    }
+
+    // Initialize emojis and motivational messages
+    let emojis = init_emojis(settings.emoji);
+    let motivations = init_motivations(settings.lang);
+
+    // Set up Ctrl+C handler for clean termination
+    let success_emojis = emojis.success.clone();
+    let rust_emojis = emojis.rust.clone();
+
+    ctrlc::set_handler(move || {
+        // Let the running timer wrap up on its own
+        if CATCH_INTERRUPT.load(Ordering::SeqCst) {
+            INTERRUPTED.store(true, Ordering::SeqCst);
+            return;
+        }
+
+        log_interrupted_work();
+        // Stopped on purpose, so there's nothing for `resume` to pick up
+        status::clear_current();
+        tui::restore();
+        keys::restore_terminal();
+        println!();
+        events::interrupted();
+        std::process::exit(0);
+    }).expect("Error setting Ctrl+C handler");
+
+    // Display welcome message on first run
+    if settings.banner {
+        print_welcome_message(&settings);
+    }
 
     // If no command is provided, run the default loop
     match &cli.command {
         Some(command) => match command {
-            Commands::Start { duration, task } => {
-                let task_desc = task.clone().unwrap_or_else(|| "no description".to_string());
-                run_work_session(*duration, &task_desc, &emojis, &motivations);
+            Commands::Start { duration, task, then_break, start_at, on_complete, intent, billing } => {
+                settings.on_complete = on_complete.clone();
+                settings.billing = billing.billing();
+                settings.intent = intent.clone().filter(|intent| !intent.trim().is_empty());
+                let task_desc = settings.task_or(task, "no description");
+                let duration = duration.unwrap_or(settings.work_minutes);
+                if let Some(start_at) = start_at
+                    && !wait_until(*start_at, &task_desc) {
+                    return;
+                }
+                if run_work_session(duration, &task_desc, &settings, None, &emojis, &motivations) != WorkEnd::StopForToday
+                    && let Some(break_minutes) = then_break {
+                    run_break(*break_minutes, false, &settings, &emojis, &motivations);
+                }
+            },
+            Commands::Sprint { task } => {
+                let task = task.clone()
+                    .or_else(|| std::env::var("POMODORO_TASK").ok().filter(|task| !task.trim().is_empty()));
+                let task_desc = settings.task_or(&task, "Focus sprint");
+                wait_for_break_gap(&settings);
+                if run_work_session(settings.work_minutes, &task_desc, &settings, None, &emojis, &motivations) != WorkEnd::StopForToday {
+                    run_break(settings.short_break_minutes, false, &settings, &emojis, &motivations);
+                }
             },
             Commands::Break { duration, long } => {
-                run_break(*duration, *long, &emojis, &motivations);
+                let default = if *long { settings.long_break_minutes } else { settings.short_break_minutes };
+                if run_break(duration.unwrap_or(default), *long, &settings, &emojis, &motivations) {
+                    let task_desc = settings.task_or(&None, "no description");
+                    run_work_session(settings.work_minutes, &task_desc, &settings, None, &emojis, &motivations);
+                }
+            },
+            Commands::SuggestBreak { work, ratio, start } => {
+                let work = work.unwrap_or(settings.work_minutes);
+                let ratio = ratio.unwrap_or(settings.break_ratio);
+                let minutes = suggested_break(work, ratio);
+                println!("☕ After {} minutes of work, take a {}-minute break", work, minutes);
+                if *start {
+                    run_break(minutes, false, &settings, &emojis, &motivations);
+                }
+            },
+            Commands::Schedule { sessions, work, short_break, long_break, task, confirm_each, long_break_after_minutes,
+                                 estimate_finish, on_complete, billing } => {
+                settings.on_complete = on_complete.clone();
+                settings.billing = billing.billing();
+                let task_desc = settings.task_or(task, "no description");
+                let plan = SchedulePlan {
+                    sessions: sessions.unwrap_or(settings.sessions),
+                    work: work.unwrap_or(settings.work_minutes),
+                    short_break: short_break.unwrap_or(settings.short_break_minutes),
+                    long_break: long_break.unwrap_or(settings.long_break_minutes),
+                    confirm_each: *confirm_each,
+                    long_break_after: *long_break_after_minutes,
+                    estimate_finish: *estimate_finish,
+                };
+                run_schedule(&plan, &task_desc, &settings, &emojis, &motivations);
+            },
+            Commands::Run { preset } => {
+                run_preset(preset, &config.presets, &settings, &emojis, &motivations);
             },
-            Commands::Schedule { sessions, work, short_break, long_break, task } => {
-                let task_desc = task.clone().unwrap_or_else(|| "no description".to_string());
-                run_schedule(*sessions, *work, *short_break, *long_break, &task_desc, &emojis, &motivations);
+            Commands::Queue { action: QueueAction::Add { task } } => {
+                match queue::add(task) {
+                    Ok((position, task)) => println!("✅ Queued at #{}: {}", position, task.bright_cyan()),
+                    Err(e) => println!("❌ Failed to queue the task: {}", e),
+                }
+            },
+            Commands::Queue { action: QueueAction::List } => {
+                let tasks = queue::list();
+                if tasks.is_empty() {
+                    println!("The queue is empty. Add tasks with `pomodoro_rs queue add \"task\"`.");
+                }
+                for (i, task) in tasks.iter().enumerate() {
+                    println!("{:>3}. {}", i + 1, task);
+                }
+            },
+            Commands::Queue { action: QueueAction::Run } => {
+                run_queue(&settings, &emojis, &motivations);
+            },
+            Commands::Presets { action: PresetsAction::List } => {
+                list_presets(&config.presets, &settings);
+            },
+            Commands::Presets { action: PresetsAction::Show { name } } => {
+                show_preset(name, &config.presets, &settings);
+            },
+            Commands::Install { options } => {
+                install_to_path(options);
             },
-            Commands::Install => {
-                install_to_path();
+            Commands::Uninstall { yes, bin_dir, assets } => {
+                uninstall(*yes, bin_dir.as_ref(), *assets);
+            },
+            Commands::Setup => {
+                run_setup(&settings);
             },
             Commands::Tip => {
-                show_random_tip(&emojis);
+                show_random_tip(&emojis, &settings);
+            },
+            Commands::Motivate { category } => {
+                show_motivation(*category, &emojis, &motivations);
+            },
+            Commands::Stats { heatmap, fractional } => {
+                if *heatmap {
+                    stats::show_heatmap(&settings.unit, settings.week_start);
+                } else {
+                    stats::show_focus_score(settings.interrupt_logging, *fractional, settings.week_start, &settings.unit);
+                }
+            },
+            Commands::Trends { weeks } => {
+                stats::show_trends(*weeks, &settings.unit, settings.week_start);
+            },
+            Commands::TaskTime { task, exact } => {
+                stats::show_task_time(task, *exact, &settings.unit);
+            },
+            Commands::Stopwatch { task } => {
+                let task_desc = settings.task_or(task, "no description");
+                run_stopwatch(&task_desc, &settings, &emojis);
+            },
+            Commands::Pick { tasks, from, duration } => {
+                let duration = duration.unwrap_or(settings.work_minutes);
+                run_pick(tasks, from.as_deref(), duration, &settings, &emojis, &motivations);
+            },
+            Commands::Report { email } => {
+                report::run_report(*email, &config, settings.interrupt_logging, settings.week_start);
+            },
+            Commands::Undo => {
+                undo_last_session();
+            },
+            Commands::Export { day, format } => {
+                // Exit non-zero so scripts can tell an empty day from an empty export
+                if let Err(e) = export::run_export(day.unwrap_or_else(|| Local::now().date_naive()), *format) {
+                    eprintln!("❌ {}", e);
+                    std::process::exit(1);
+                }
+            },
+            Commands::OpenLog { day } => {
+                open_log(day.unwrap_or_else(|| Local::now().date_naive()));
+            },
+            Commands::Import { from, file, mapping } => {
+                import::run_import(*from, file, mapping.as_deref(), settings.log_format);
+            },
+            Commands::Present { state } => {
+                let on = *state == Toggle::On;
+                match status::set_presenting(on) {
+                    Ok(()) if on => println!("🔇 Presentation mode on: sounds and notifications are silenced until `pomodoro_rs present off`"),
+                    Ok(()) => println!("🔔 Presentation mode off: sounds and notifications are back"),
+                    Err(e) => println!("❌ Failed to change presentation mode: {}", e),
+                }
+            },
+            Commands::Remaining | Commands::Menubar { .. } => unreachable!("handled before startup"),
+            Commands::Resume => {
+                if status::remaining().is_some() {
+                    println!("❌ A timer is still running; see `pomodoro_rs remaining`");
+                    return;
+                }
+                let Some(saved) = status::saved_timer() else {
+                    println!("❌ No interrupted session to resume");
+                    return;
+                };
+                // Breaks aren't worth picking back up
+                if saved.timer_type.contains("Break") {
+                    status::clear_current();
+                    println!("❌ Only work sessions can be resumed; the last timer was a {}", saved.timer_type.to_lowercase());
+                    return;
+                }
+                resume_work_session(saved, &settings, &emojis, &motivations);
+            },
+            Commands::PreviewSound => {
+                preview_sound(&settings.sound);
+            },
+            Commands::SoundInfo => {
+                show_sound_info(&settings.sound);
+            },
+            Commands::Invoice { client, month } => {
+                invoice::show_timesheet(client, *month);
+            },
+            Commands::Remind { every, message } => {
+                run_remind(every.unwrap_or(settings.work_minutes), message.as_deref(), &settings, &emojis);
             },
         },
         None => {
             // Default loop - repeat 25/5 pattern until user exits
-            println!("{} Starting default Pomodoro cycle (25min work, 5min break) {}\n",
+            println!("{} Starting default {} cycle ({}min work, {}min break) {}\n",
                      random_from(&emojis.work),
+                     settings.unit.title(),
+                     settings.work_minutes,
+                     settings.short_break_minutes,
                      random_from(&emojis.rust));
 
             println!("{}", "Press Ctrl+C at any time to exit.".yellow());
 
+            // Offered again on the next cycle so Enter keeps the same task
+            let mut previous_task: Option<String> = None;
+
             loop {
                 // Ask for task description
-                let task = dialoguer::Input::<String>::new()
-                    .with_prompt("What are you working on? (optional)")
-                    .allow_empty(true)
-                    .interact_text()
-                    .unwrap_or_else(|_| "".to_string());
-
-                let task_desc = if task.is_empty() { "Focused work".to_string() } else { task };
-
-                // Run work session
-                run_work_session(25, &task_desc, &emojis, &motivations);
-
-                // Run break
-                run_break(5, false, &emojis, &motivations);
-
-                // Ask if user wants to continue
-                if !Confirm::with_theme(&ColorfulTheme::default())
-                    .with_prompt("Start another Pomodoro cycle?")
-                    .default(true)
-                    .interact()
-                    .unwrap_or(false) {
-
-                    println!("\n{} Thanks for using Pomodoro_rs! Have a productive day! {}\n",
-                             random_from(&emojis.rust),
-                             random_from(&emojis.success));
+                let mut prompt = dialoguer::Input::<String>::new();
+                prompt.with_prompt("What are you working on? (optional)")
+                    .allow_empty(true);
+                if let Some(previous) = previous_task.clone().or_else(|| settings.from_git.then(last_commit_subject).flatten()) {
+                    prompt.default(previous);
+                }
+                let task = prompt.interact_text().unwrap_or_else(|_| "".to_string());
+
+                // Only a typed task is offered next time, so `{n}` labels keep counting
+                let task_desc = if task.is_empty() {
+                    settings.task_or(&None, "Focused work")
+                } else {
+                    previous_task = Some(task.clone());
+                    task
+                };
+
+                // Run work session, once you've rested long enough, stopping if you're done for the day
+                wait_for_break_gap(&settings);
+                if run_work_session(settings.work_minutes, &task_desc, &settings, None, &emojis, &motivations) == WorkEnd::StopForToday {
+                    if settings.eod_summary {
+                        report::print_day_summary(settings.daily_goal, &settings.unit);
+                    }
+                    break;
+                }
+
+                // Run break, going straight back to work if it was skipped
+                if run_break(settings.short_break_minutes, false, &settings, &emojis, &motivations) {
+                    continue;
+                }
+
+                // Ask if user wants to continue, giving up if nobody answers
+                if !confirm_with_timeout(&format!("Start another {} cycle?", settings.unit.title()), settings.idle_exit_minutes) {
+                    if settings.motivation {
+                        println!("\n{} Thanks for using Pomodoro_rs! Have a productive day! {}\n",
+                                 random_from(&emojis.rust),
+                                 random_from(&emojis.success));
+                    }
+                    if settings.eod_summary {
+                        report::print_day_summary(settings.daily_goal, &settings.unit);
+                    }
                     break;
                 }
             }
         }
     }
+
+    // Let any alert that's still playing finish
+    sound::finish();
+    team::finish();
+}
+
+/// Remove the last completed session from today's log and show what was removed
+fn undo_last_session() {
+    match history::remove_last_entry(history::COMPLETED_DIR, Local::now().date_naive()) {
+        Ok(Some(entry)) => {
+            history::bump_lifetime_count(-1);
+
+            let minutes = entry.minutes.map(|m| format!(" ({} min)", m)).unwrap_or_default();
+            println!("{} {} {}{}",
+                     "Removed:".bright_yellow(),
+                     entry.time.format("%H:%M:%S"),
+                     entry.task.bright_cyan(),
+                     minutes);
+        },
+        Ok(None) => println!("{}", "No sessions logged today, nothing to undo.".yellow()),
+        Err(e) => eprintln!("❌ Failed to undo: {}", e),
+    }
 }
 
-/// Display a welcome message with ASCII art
-fn print_welcome_message(_emojis: &Emojis) {
+/// Print the welcome banner: the art in `banner_file` if it can be read, else the built-in one
+fn print_welcome_message(settings: &Settings) {
+    if let Some(path) = &settings.banner_file {
+        match std::fs::read_to_string(path) {
+            Ok(art) => {
+                println!("{}", art.trim_end().bright_red());
+                println!();
+                return;
+            },
+            Err(e) => eprintln!("⚠️ Couldn't read banner_file {:?}: {}", path, e),
+        }
+    }
+
     println!("{}", r#"
     ╔═══════════════════════════════════════════╗
     ║                                           ║
@@ -256,212 +1606,1620 @@ fn print_welcome_message(_emojis: &Emojis) {
     "#.bright_red());
 }
 
-/// Log completed task to daily file
-fn log_completed_task(task_desc: &str) {
-    if let Some(home) = home_dir() {
-        let completed_dir = home.join(".completed_tasks");
+/// With `--track-window`, the title of the window in focus, warning when it can't be read
+fn focused_window(settings: &Settings) -> Option<String> {
+    if !settings.track_window {
+        return None;
+    }
+
+    match window::active_title() {
+        Ok(title) => Some(title),
+        Err(e) => {
+            eprintln!("⚠️ Couldn't read the active window: {}", e);
+            None
+        },
+    }
+}
+
+/// Run a work session with timer and motivational messages.
+/// `session` is the position in a schedule as (index, total), named in the notification.
+fn run_work_session(minutes: u64, task_desc: &str, settings: &Settings, session: Option<(u32, u32)>,
+                    emojis: &Emojis, motivations: &Motivations) -> WorkEnd {
+    if !within_daily_cap(settings) {
+        println!("{} Good call, see you tomorrow!", random_from(&emojis.success));
+        return WorkEnd::StopForToday;
+    }
+
+    let task_desc = &settings.number_untitled(task_desc);
+    let work_emoji = random_from(&emojis.work);
+    let rust_emoji = random_from(&emojis.rust);
+
+    // println!("\n{} {} {}", work_emoji, random_from(&motivations.start_work).bright_green(), rust_emoji);
+    // println!("{} Starting {} minute Pomodoro for: {}\n",
+             // work_emoji,
+             // minutes.to_string().bright_yellow(),
+             // task_desc.bright_cyan());
+
+    if settings.tip_on_start {
+        show_random_tip(emojis, settings);
+    }
+
+    // Record the start so unfinished sessions lower the focus score
+    let counts = minutes >= settings.min_count_minutes;
+    if counts && !settings.no_log && settings.interrupt_logging {
+        history::log_started_task(task_desc, settings.log_format);
+    }
+
+    if let Some(intent) = &settings.intent {
+        println!("\n🎯 {} {}\n", "Intent:".bold(), intent.bright_white().bold());
+    }
+
+    sound::play_start_sound(&settings.sound);
+    let started = Local::now();
+    let window = focused_window(settings);
+    if counts && !settings.no_log && settings.interrupt_logging
+        && let Ok(mut running) = RUNNING_WORK.lock() {
+        *running = Some(RunningWork {
+            task: task_desc.to_string(),
+            started,
+            planned: minutes,
+            rounding: settings.log_rounding,
+            format: settings.log_format,
+        });
+    }
+    events::work_started(&settings.unit.title(), task_desc, Some(minutes * 60));
+    let end = run_fancy_timer(minutes * 60, &settings.unit.title(), task_desc, settings, &[], &emojis.work, &motivations.during_work);
+    if let Ok(mut running) = RUNNING_WORK.lock() {
+        *running = None;
+    }
+
+    // A skipped session is left as started, like an interrupted one
+    let seconds = match end {
+        TimerEnd::Finished { seconds } => seconds,
+        TimerEnd::Stopped => {
+            events::interrupted();
+            println!("{}", "Session skipped, not logged.".yellow());
+            return WorkEnd::Skipped;
+        },
+    };
+
+    let minutes = finish_work_session(task_desc, seconds, started, window.as_deref(), settings, session, emojis);
+    events::work_completed(&settings.unit.title(), task_desc, minutes);
+    WorkEnd::Completed(minutes)
+}
+
+/// Pick up the work session left running when the machine crashed or rebooted,
+/// counting toward its original end. One that ran out meanwhile completes straight away.
+fn resume_work_session(saved: status::SavedTimer, settings: &Settings, emojis: &Emojis, motivations: &Motivations) {
+    let task_desc = &saved.task;
+    let done = saved.total - saved.remaining.min(saved.total);
+
+    let seconds = if saved.remaining == 0 {
+        status::clear_current();
+        println!("{} {} for {} ended while pomodoro_rs wasn't running",
+                 random_from(&emojis.work), saved.timer_type, task_desc.bright_cyan());
+        saved.total
+    } else {
+        println!("{} Resuming {} for {} with {} left",
+                 random_from(&emojis.work), saved.timer_type, task_desc.bright_cyan(),
+                 format_time_left(saved.remaining).bright_yellow());
+
+        if !settings.no_log && settings.interrupt_logging
+            && let Ok(mut running) = RUNNING_WORK.lock() {
+            *running = Some(RunningWork {
+                task: task_desc.to_string(),
+                started: saved.started,
+                planned: saved.total / 60,
+                rounding: settings.log_rounding,
+                format: settings.log_format,
+            });
+        }
+        events::work_started(&saved.timer_type, task_desc, Some(saved.remaining));
+        let end = run_fancy_timer(saved.remaining, &saved.timer_type, task_desc, settings, &[],
+                                  &emojis.work, &motivations.during_work);
+        if let Ok(mut running) = RUNNING_WORK.lock() {
+            *running = None;
+        }
+
+        match end {
+            TimerEnd::Finished { seconds } => done + seconds,
+            TimerEnd::Stopped => {
+                events::interrupted();
+                println!("{}", "Session skipped, not logged.".yellow());
+                return;
+            },
+        }
+    };
+
+    let minutes = finish_work_session(task_desc, seconds, saved.started, None, settings, None, emojis);
+    events::work_completed(&saved.timer_type, task_desc, minutes);
+}
+
+/// Log a work session that ran for `seconds`, then announce it.
+/// Returns the minutes logged, or that would have been.
+fn finish_work_session(task_desc: &str, seconds: u64, started: DateTime<Local>, window: Option<&str>,
+                       settings: &Settings, session: Option<(u32, u32)>, emojis: &Emojis) -> u64 {
+    // Time may have been added or taken off along the way
+    let minutes = settings.log_rounding.minutes(seconds);
+    let counts = minutes >= settings.min_count_minutes;
+
+    let intent = settings.intent.as_ref().map(|text| history::Intent {
+        text: text.clone(),
+        achieved: ask_intent_achieved(text),
+    });
+
+    // Log the completed task, unless it was too short to count
+    if !counts {
+        println!("{}", format!("Session shorter than {} min, not counted toward stats.",
+                               settings.min_count_minutes).dimmed());
+    } else if !settings.no_log {
+        if logging_cancelled(settings.grace_period) {
+            println!("{}", "Logging cancelled, session not counted.".yellow());
+        } else {
+            let completion = history::Completion {
+                started,
+                billing: settings.billing.as_ref(),
+                window,
+                intent: intent.as_ref(),
+                attribute_by: settings.attribute_by,
+            };
+            let total = history::log_completed_task(task_desc, minutes, completion, settings.log_format);
+            if let Some(team) = &settings.team {
+                team::record_completion(team, task_desc, minutes);
+            }
+            count_for_project(task_desc, settings);
+            #[cfg(feature = "syslog")]
+            if settings.syslog {
+                syslog::record_completion(task_desc, minutes);
+            }
+            if settings.count_sessions {
+                announce_lifetime_count(total, settings.count_sessions_every, &settings.unit, emojis);
+            }
+            nudge_toward_goal(settings);
+            if let Some(command) = &settings.on_complete {
+                run_on_complete(command, task_desc, minutes, settings.verbose);
+            }
+        }
+    }
+
+    // println!("\n{} {} {}",
+             // random_from(&emojis.success),
+             // random_from(&motivations.end_work).bright_green(),
+             // rust_emoji);
+
+    if let Some(format) = &settings.copy_format {
+        let text = i18n::fill(format, &[("task", task_desc), ("minutes", &minutes.to_string())]);
+        match clipboard::copy(&text) {
+            Ok(()) => println!("{}", format!("📋 Copied \"{}\"", text).dimmed()),
+            Err(e) => eprintln!("⚠️ Couldn't copy to the clipboard: {}", e),
+        }
+    }
+
+    // This will play the alert sound
+    let lang = settings.lang;
+    let title = match session {
+        Some((index, total)) => i18n::fill(lang.text(|l| l.session_done_title), &[
+            ("index", &index.to_string()),
+            ("total", &total.to_string()),
+        ]),
+        None => i18n::fill(lang.text(|l| l.work_done_title), &[("Unit", &settings.unit.title())]),
+    };
+    notify(&title,
+           &i18n::fill(lang.text(|l| l.work_done_body), &[
+               ("emoji", random_from(&emojis.success)),
+               ("minutes", &minutes.to_string()),
+               ("task", task_desc),
+               ("unit", settings.unit.count(1)),
+           ]), settings);
+
+    minutes
+}
+
+/// Ask whether the session's intent was met; `None` if it can't be asked or goes unanswered
+fn ask_intent_achieved(intent: &str) -> Option<bool> {
+    if !io::stdin().is_terminal() {
+        return None;
+    }
+
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Did you achieve your intent: \"{}\"?", intent))
+        .interact_opt()
+        .ok()
+        .flatten()
+}
+
+/// Count down `seconds` before a session is logged; returns true if `c` was pressed to cancel.
+/// Never cancels when keys can't be read, so unattended runs always log.
+fn logging_cancelled(seconds: u64) -> bool {
+    if seconds == 0 {
+        return false;
+    }
+
+    let keys = Some(KeyListener::new());
+    if !keys.as_ref().is_some_and(KeyListener::is_active) {
+        return false;
+    }
+
+    for left in (1..=seconds).rev() {
+        print!("\r{}  ", format!("Logging in {}s — press c to cancel", left).dimmed());
+        io::stdout().flush().unwrap();
+
+        if wait_one_second(&keys, &['c', 'C']).is_some() {
+            println!();
+            return true;
+        }
+    }
+
+    println!();
+    false
+}
+
+/// Celebrate round-number milestones, and mention the all-time total every `every` sessions
+fn announce_lifetime_count(total: u64, every: u64, unit: &i18n::UnitLabel, emojis: &Emojis) {
+    let milestone = matches!(total, 100 | 250 | 500) || (total >= 1000 && total.is_multiple_of(1000));
+
+    if milestone {
+        println!("{} {} {}",
+                 random_from(&emojis.success),
+                 format!("Milestone: {} {} all-time!", with_thousands(total), unit.count(total)).bright_green(),
+                 random_from(&emojis.success));
+    } else if every > 0 && total.is_multiple_of(every) {
+        println!("You've completed {} {} all-time {}",
+                 with_thousands(total).bright_yellow(),
+                 unit.count(total),
+                 random_from(&emojis.rust));
+    }
+}
+
+/// With `--project-file`, add the session to its `@project`'s shared count
+fn count_for_project(task_desc: &str, settings: &Settings) {
+    let (Some(path), Some(project)) = (&settings.project_file, project::project_of(task_desc)) else {
+        return;
+    };
+
+    match project::record(path, project) {
+        Ok(total) => println!("{}", format!("📁 @{}: {} {}", project, total, settings.unit.count(total)).dimmed()),
+        Err(e) => eprintln!("⚠️ Couldn't update the project file: {}", e),
+    }
+}
+
+/// Run the `--on-complete` command through the shell, with the task and minutes
+/// filled in. Its output is hidden unless `verbose`, and a failure only warns.
+fn run_on_complete(command: &str, task_desc: &str, minutes: u64, verbose: bool) {
+    let command = command.replace("{task}", &shell_quote(task_desc))
+        .replace("{minutes}", &minutes.to_string());
+
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", &command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", &command]);
+        shell
+    };
 
-        // Create directory if it doesn't exist
-        if let Err(_) = create_dir_all(&completed_dir) {
+    let output = match shell.output() {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("⚠️ Couldn't run the --on-complete command: {}", e);
             return;
         }
+    };
+
+    if verbose {
+        io::stdout().write_all(&output.stdout).ok();
+        io::stderr().write_all(&output.stderr).ok();
+    }
+    if !output.status.success() {
+        eprintln!("⚠️ The --on-complete command failed ({})", output.status);
+    }
+}
+
+/// Quote a value so the shell passes it through as one argument, whatever it contains
+fn shell_quote(value: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// With `--catch-up`, mention how far off the daily goal you are once it's getting late
+fn nudge_toward_goal(settings: &Settings) {
+    let (Some(goal), Some(hour)) = (settings.daily_goal, settings.catch_up_hour) else {
+        return;
+    };
+
+    let now = Local::now();
+    let done = history::read_day(history::COMPLETED_DIR, now.date_naive()).len() as u64;
+    if done >= goal || now.hour() < hour {
+        return;
+    }
+
+    let left = goal - done;
+    println!("{}", format!("⏰ {} {} left to hit your goal of {}, and it's {}",
+                           left,
+                           settings.unit.count(left),
+                           goal,
+                           now.format("%-I:%M%P")).yellow());
+}
+
+/// Set once you've chosen to keep going past `--max-daily-minutes`, so you're only asked once a run
+static PAST_DAILY_CAP: AtomicBool = AtomicBool::new(false);
+
+/// With `--max-daily-minutes`, check today's logged minutes before a work session starts.
+/// Over the cap, suggest resting and ask whether to carry on; returns false to stop.
+/// Without anyone at the keyboard to ask, it only warns.
+fn within_daily_cap(settings: &Settings) -> bool {
+    let Some(cap) = settings.max_daily_minutes else {
+        return true;
+    };
+    if PAST_DAILY_CAP.load(Ordering::SeqCst) {
+        return true;
+    }
+
+    let today: u64 = history::read_day(history::COMPLETED_DIR, Local::now().date_naive()).iter()
+        .filter_map(|entry| entry.minutes)
+        .sum();
+    if today < cap {
+        return true;
+    }
+
+    println!("\n{}", format!("🌙 You've worked enough today ({}h {:02}m, your cap is {}h {:02}m) — consider resting.",
+                             today / 60, today % 60, cap / 60, cap % 60).yellow());
+    if !io::stdin().is_terminal() {
+        return true;
+    }
+
+    let proceed = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Start another session anyway?")
+        .default(false)
+        .interact()
+        .unwrap_or(false);
+    PAST_DAILY_CAP.store(proceed, Ordering::SeqCst);
+    proceed
+}
+
+/// With `--min-break-gap`, hold off starting a session until that many minutes have passed
+/// since the last completed one in the log, counting down what's left. Ctrl+C still quits.
+fn wait_for_break_gap(settings: &Settings) {
+    let Some(gap) = settings.min_break_gap else {
+        return;
+    };
+
+    // Yesterday too, for a session that finished just before midnight
+    let today = Local::now().date_naive();
+    let last = [today, today.pred_opt().unwrap_or(today)].into_iter()
+        .find_map(|date| {
+            let last = history::read_day(history::COMPLETED_DIR, date).iter().map(|entry| entry.time).max()?;
+            Local.from_local_datetime(&date.and_time(last)).earliest()
+        });
+    let Some(last) = last else {
+        return;
+    };
+
+    let ready = last + chrono::Duration::minutes(gap as i64);
+    if ready <= Local::now() {
+        return;
+    }
+
+    println!("\n{}", format!("🧘 Your last session ended at {}; rest for at least {} min before the next one.",
+                             last.format("%H:%M"), gap).yellow());
+    let mut status = StatusLine::new(None);
+    loop {
+        let millis = ready.signed_duration_since(Local::now()).num_milliseconds();
+        if millis <= 0 {
+            break;
+        }
+        status.show(&ready.format("%H:%M").to_string(), (millis as u64).div_ceil(1000), "resting before the next session");
+        thread::sleep(Duration::from_millis(millis.min(1000) as u64));
+    }
+    println!();
+}
+
+/// Format a number with thousands separators, e.g. `1,204`
+fn with_thousands(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(digit);
+    }
+
+    out
+}
+
+/// Run a break session with timer and motivational messages.
+/// Returns true if the break was abandoned to keep working.
+fn run_break(minutes: u64, is_long: bool, settings: &Settings, emojis: &Emojis, motivations: &Motivations) -> bool {
+    let break_type = if is_long { "long" } else { "short" };
+    let break_emojis = if is_long { &emojis.break_long } else { &emojis.break_short };
+    let break_emoji = random_from(break_emojis);
+    let rust_emoji = random_from(&emojis.rust);
+
+    // println!("\n{} {} {}", break_emoji, random_from(&motivations.start_break).bright_blue(), rust_emoji);
+    // println!("{} Starting {} minute {} break\n",
+             // break_emoji,
+             // minutes.to_string().bright_yellow(),
+             // break_type.bright_magenta());
+
+    let (description, stop_keys) = if settings.flexible_breaks {
+        (format!("Time to relax (press {} to keep working)", KEEP_WORKING_KEY), vec![KEEP_WORKING_KEY])
+    } else {
+        ("Time to relax".to_string(), vec![])
+    };
+
+    let timer_type = format!("{} Break", if is_long { "Long" } else { "Short" });
+    let started = Local::now();
+    events::break_started(&timer_type, minutes * 60);
+    let end = run_fancy_timer(minutes * 60, &timer_type,
+                  &description, settings, &stop_keys, break_emojis, &motivations.start_break);
+
+    if let TimerEnd::Stopped = end {
+        events::interrupted();
+        let taken = Local::now().signed_duration_since(started).num_minutes();
+        if !settings.no_log {
+            history::log_skipped_break(&format!("{} break ({}/{} min)", break_type, taken, minutes), settings.log_format);
+        }
+        println!("{}", format!("Skipped the {} break after {} min, back to work!", break_type, taken).dimmed());
+        return true;
+    }
+
+    // println!("\n{} {} {}",
+             // random_from(&emojis.success),
+             // random_from(&motivations.end_break).bright_green(),
+             // rust_emoji);
+
+    events::break_ended(&timer_type, minutes);
+    let lang = settings.lang;
+    notify(lang.text(|l| l.break_done_title),
+           &i18n::fill(lang.text(|l| l.break_done_body), &[
+               ("emoji", random_from(&emojis.success)),
+               ("minutes", &minutes.to_string()),
+           ]), settings);
+
+    if settings.log_breaks {
+        let note = dialoguer::Input::<String>::new()
+            .with_prompt("How did you spend your break? (optional)")
+            .allow_empty(true)
+            .interact_text()
+            .unwrap_or_default();
+        if !note.trim().is_empty() {
+            history::log_break_note(note.trim(), minutes, settings.log_format);
+        }
+    }
+
+    false
+}
+
+/// Count up from zero until a key press or Ctrl+C, then log the elapsed minutes
+fn run_stopwatch(task_desc: &str, settings: &Settings, emojis: &Emojis) {
+    let task_desc = &settings.number_untitled(task_desc);
+    let start_time = Local::now();
+    let window = focused_window(settings);
+    let description = format!("{} (press any key to stop)", task_desc);
+
+    let keys = KeyListener::new();
+    let mut status = StatusLine::new(None);
+    CATCH_INTERRUPT.store(true, Ordering::SeqCst);
+    events::work_started("Stopwatch", task_desc, None);
+
+    let elapsed_secs = loop {
+        let elapsed_secs = Local::now().signed_duration_since(start_time).num_seconds().max(0) as u64;
+        status.show(&start_time.format("%H:%M:%S").to_string(), elapsed_secs, &description);
+
+        if keys.poll().is_some() || INTERRUPTED.load(Ordering::SeqCst) {
+            break elapsed_secs;
+        }
+        thread::sleep(Duration::from_millis(100));
+    };
+
+    CATCH_INTERRUPT.store(false, Ordering::SeqCst);
+    drop(keys);
+    println!();
+
+    let minutes = settings.log_rounding.minutes(elapsed_secs);
+    println!("{} Tracked {} min on: {}",
+             random_from(&emojis.success),
+             minutes.to_string().bright_yellow(),
+             task_desc.bright_cyan());
+
+    // An open-ended session is both started and completed when it stops, so its start
+    // is only recorded now, under the same conditions as a work session's
+    if minutes >= settings.min_count_minutes && !settings.no_log && settings.interrupt_logging {
+        history::log_started_task(task_desc, settings.log_format);
+    }
+    let minutes = finish_work_session(task_desc, elapsed_secs, start_time, window.as_deref(), settings, None, emojis);
+    events::work_completed("Stopwatch", task_desc, minutes);
+}
+
+/// Open the completed-sessions log for `date` in the user's editor, creating it if needed
+fn open_log(date: chrono::NaiveDate) {
+    let Some(path) = history::day_file(history::COMPLETED_DIR, date) else {
+        println!("❌ Could not determine the home directory");
+        return;
+    };
+
+    let created = path.parent().is_some_and(|dir| std::fs::create_dir_all(dir).is_ok())
+        && std::fs::OpenOptions::new().create(true).append(true).open(&path).is_ok();
+    if !created {
+        println!("❌ Could not create {:?}", path);
+        return;
+    }
+
+    // $VISUAL, then $EDITOR, which may carry arguments like `code --wait`
+    let editor = std::env::var("VISUAL").ok()
+        .or_else(|| std::env::var("EDITOR").ok())
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or_default();
+
+    match Command::new(program).args(words).arg(&path).status() {
+        Ok(status) if status.success() => (),
+        Ok(status) => println!("⚠️ {} exited with {}", program, status),
+        Err(e) => println!("❌ Could not start {:?}: {} (set $EDITOR to choose another editor)", program, e),
+    }
+}
+
+/// Play the alert that timers would use and report exactly what happened
+fn preview_sound(sound_settings: &sound::SoundSettings) {
+    if !sound_settings.enabled {
+        println!("{}", "Sounds are turned off (--no-sound or `no_sound` in your config).".yellow());
+        return;
+    }
+
+    if let Some(custom) = sound_settings.custom.as_ref().filter(|path| !path.is_file()) {
+        println!("⚠️ Sound file {:?} not found, falling back to the default alert", custom);
+    } else if sound_settings.custom.is_none() && sound_settings.sound_dir.is_none()
+        && sound::find_sound(sound_settings.theme.complete(), sound_settings).is_none() {
+        println!("⚠️ Sound theme file {} not found, falling back to the default alert",
+                 sound_settings.theme.complete());
+    }
+
+    let Some(path) = sound::resolve_alert(sound_settings) else {
+        println!("❌ No alert sound available");
+        return;
+    };
+
+    println!("🔊 Playing {:?}...", path);
+    match sound::play_file(&path) {
+        Ok(()) => println!("✅ Sound played."),
+        Err(e) => println!("❌ Could not play sound: {}", e),
+    }
+}
+
+/// Describe the alert that timers would use, e.g. to time a custom sound
+fn show_sound_info(sound_settings: &sound::SoundSettings) {
+    // Unlike timers, don't quietly fall back: the point is to check this file
+    let path = match &sound_settings.custom {
+        Some(custom) if !custom.is_file() => {
+            println!("❌ Sound file {:?} not found", custom);
+            return;
+        },
+        Some(custom) => custom.clone(),
+        None => match sound::resolve_alert(sound_settings) {
+            Some(path) => path,
+            None => {
+                println!("❌ No alert sound available");
+                return;
+            },
+        },
+    };
+
+    let info = match sound::wav_info(&path) {
+        Ok(info) => info,
+        Err(e) => {
+            println!("❌ Could not read {:?}: {}", path, e);
+            return;
+        },
+    };
+
+    println!("\n🔊 {}", path.display().to_string().bright_yellow());
+    println!("  Format:      {}, {}-bit", info.encoding, info.bits_per_sample);
+    println!("  Sample rate: {} Hz", info.sample_rate);
+    println!("  Channels:    {}", info.channels);
+    println!("  Duration:    {:.2} s\n", info.duration_seconds);
+}
+
+/// Send a break reminder every `every` minutes until Ctrl+C
+fn run_remind(every: u64, message: Option<&str>, settings: &Settings, emojis: &Emojis) {
+    println!("{} Reminding you to take a break every {} min. {}",
+             random_from(&emojis.break_short),
+             every.to_string().bright_yellow(),
+             "Press Ctrl+C to stop.".yellow());
+
+    loop {
+        let next = Local::now() + chrono::Duration::minutes(every as i64);
+        println!("{}", format!("Next reminder at {}", format_eta(next, &SystemClock, settings.round_eta)).dimmed());
+        thread::sleep(Duration::from_secs(every * 60));
+
+        let body = match message {
+            Some(message) => message.to_string(),
+            None => format!("{} You've been at it for {} minutes. Stretch, hydrate, look away from the screen!",
+                            random_from(&emojis.break_short), every),
+        };
+        notify("Time for a break?", &body, settings);
+    }
+}
+
+/// Choose a random task from the arguments and/or a file, then work on it
+fn run_pick(tasks: &[String], from: Option<&Path>, minutes: u64, settings: &Settings,
+            emojis: &Emojis, motivations: &Motivations) {
+    let mut candidates = tasks.to_vec();
+
+    if let Some(path) = from {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => candidates.extend(contents.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string)),
+            Err(e) => {
+                println!("❌ Failed to read {:?}: {}", path, e);
+                return;
+            }
+        }
+    }
+
+    let Some(task) = candidates.choose(&mut thread_rng()) else {
+        println!("❌ No tasks to pick from. Pass some tasks or use --from <FILE>.");
+        return;
+    };
+
+    println!("{} Picked: {}\n", random_from(&emojis.work), task.bright_cyan());
+    run_work_session(minutes, task, settings, None, emojis, motivations);
+}
+
+/// Work through the queue from the top, one work session and short break per task.
+/// A task leaves the queue once its session is over, so quitting or skipping midway keeps it
+/// for next time; the queue is read again before each task, so ones added meanwhile are picked up.
+fn run_queue(settings: &Settings, emojis: &Emojis, motivations: &Motivations) {
+    if queue::list().is_empty() {
+        println!("❌ The queue is empty. Add tasks with `pomodoro_rs queue add \"task\"`.");
+        return;
+    }
+
+    let mut done = 0;
+    loop {
+        let tasks = queue::list();
+        let Some(task) = tasks.first() else {
+            break;
+        };
+
+        println!("{} Next up, {} in the queue: {}\n",
+                 random_from(&emojis.work), tasks.len(), task.bright_cyan());
+        // Stopped at the daily cap, or skipped: the task stays at the top for next time
+        if !matches!(run_work_session(settings.work_minutes, task, settings, None, emojis, motivations), WorkEnd::Completed(_)) {
+            println!("{} Queue stopped: {} task{} done, {} left for next time.",
+                     random_from(&emojis.success), done, if done == 1 { "" } else { "s" }, tasks.len());
+            return;
+        }
+        if let Err(e) = queue::remove(task) {
+            println!("❌ Failed to take {:?} off the queue: {}", task, e);
+            return;
+        }
+        done += 1;
+
+        run_break(settings.short_break_minutes, false, settings, emojis, motivations);
+    }
+
+    println!("{} Queue cleared: {} task{} done!",
+             random_from(&emojis.success), done, if done == 1 { "" } else { "s" });
+}
+
+/// Run the schedule saved as `[presets.<name>]`, filling gaps from the usual settings
+fn run_preset(name: &str, presets: &BTreeMap<String, Preset>, settings: &Settings,
+              emojis: &Emojis, motivations: &Motivations) {
+    let Some(preset) = find_preset(name, presets) else {
+        return;
+    };
+
+    let task_desc = settings.task_or(&preset.task, "no description");
+    let plan = SchedulePlan::from_preset(preset, settings);
+    run_schedule(&plan, &task_desc, settings, emojis, motivations);
+}
+
+/// Look up a preset, saying which ones exist when it doesn't
+fn find_preset<'a>(name: &str, presets: &'a BTreeMap<String, Preset>) -> Option<&'a Preset> {
+    let preset = presets.get(name);
+    if preset.is_none() {
+        if presets.is_empty() {
+            println!("❌ No preset named {:?}, and none are defined. Add a [presets.<name>] section to your config.", name);
+        } else {
+            let names: Vec<&str> = presets.keys().map(String::as_str).collect();
+            println!("❌ No preset named {:?}. Available presets: {}", name, names.join(", "));
+        }
+    }
+    preset
+}
+
+/// Print each preset's name and what it runs, e.g. `study  4 × 25 min, 5/15 min breaks, ~2h 5m`
+fn list_presets(presets: &BTreeMap<String, Preset>, settings: &Settings) {
+    if presets.is_empty() {
+        println!("No presets defined. Add a [presets.<name>] section to your config.");
+        return;
+    }
+
+    let width = presets.keys().map(|name| name.chars().count()).max().unwrap_or(0);
+    for (name, preset) in presets {
+        let plan = SchedulePlan::from_preset(preset, settings);
+        let total = plan.total_minutes();
+        let padding = " ".repeat(width - name.chars().count());
+        let mut line = format!("{}{}  {} × {} min, {}/{} min breaks, ~{}h {:02}m",
+                               name.bold(), padding, plan.sessions, plan.work,
+                               plan.short_break, plan.long_break, total / 60, total % 60);
+        if let Some(task) = &preset.task {
+            line.push_str(&format!(" — {}", task));
+        }
+        println!("{}", line);
+    }
+}
+
+/// Print every setting of one preset, marking those taken from your usual settings
+fn show_preset(name: &str, presets: &BTreeMap<String, Preset>, settings: &Settings) {
+    let Some(preset) = find_preset(name, presets) else {
+        return;
+    };
+
+    let plan = SchedulePlan::from_preset(preset, settings);
+    let source = |set: bool| if set { String::new() } else { " (default)".dimmed().to_string() };
+    // As it would be written in the config, quoted if it isn't a bare TOML key
+    let key = if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        name.to_string()
+    } else {
+        json::string(name)
+    };
+    println!("{}", format!("[presets.{}]", key).bold());
+    println!("  sessions                  {}{}", plan.sessions, source(preset.sessions.is_some()));
+    println!("  work                      {} min{}", plan.work, source(preset.work.is_some()));
+    println!("  short_break               {} min{}", plan.short_break, source(preset.short_break.is_some()));
+    println!("  long_break                {} min{}", plan.long_break, source(preset.long_break.is_some()));
+    println!("  task                      {}", match &preset.task {
+        Some(task) => task.clone(),
+        None => settings.task_or(&None, "no description") + &source(false),
+    });
+    println!("  confirm_each              {}{}", plan.confirm_each, source(preset.confirm_each.is_some()));
+    match plan.long_break_after {
+        Some(minutes) => println!("  long_break_after_minutes  {} min", minutes),
+        None => println!("  long_break_after_minutes  {}", "not set, long break at the end".dimmed()),
+    }
+
+    let total = plan.total_minutes();
+    println!("\nAbout {}h {:02}m from the first session to the end of the last break.", total / 60, total % 60);
+}
+
+/// Run a schedule of pomodoro sessions with breaks
+fn run_schedule(plan: &SchedulePlan, task_desc: &str, settings: &Settings,
+               emojis: &Emojis, motivations: &Motivations) {
+    let SchedulePlan { sessions, work, short_break, long_break, long_break_after, .. } = *plan;
+    // Minutes worked since the last long break
+    let mut worked = 0;
+    let rust_emoji = random_from(&emojis.rust);
+
+    // Prompts need someone at the keyboard
+    let confirm_each = plan.confirm_each && io::stdin().is_terminal();
+
+    println!("{} Scheduling {} work sessions ({} min) with short breaks ({} min) and a long break ({} min) {}",
+             random_from(&emojis.work),
+             sessions.to_string().bright_yellow(),
+             work.to_string().bright_green(),
+             short_break.to_string().bright_blue(),
+             long_break.to_string().bright_magenta(),
+             rust_emoji);
+
+    if plan.estimate_finish {
+        let total = plan.total_minutes();
+        let finish = Local::now() + chrono::Duration::minutes(total as i64);
+        // Anything that waits on you, or on the battery, pushes the end back
+        let approximate = confirm_each || settings.grace_period > 0 || settings.pause_on_low_battery.is_some();
+        println!("🏁 This will finish at {} ({}h {:02}m{}).",
+                 format_eta(finish, &SystemClock, true).bright_green(),
+                 total / 60,
+                 total % 60,
+                 if approximate { ", roughly: prompts and pauses add time" } else { "" });
+    }
+
+    for i in 1..=sessions {
+        println!("\n{} {} === Session {}/{} === {} {}",
+                 random_from(&emojis.work),
+                 "🔄".bright_yellow(),
+                 i.to_string().bright_yellow(),
+                 sessions.to_string().bright_yellow(),
+                 "🔄".bright_yellow(),
+                 random_from(&emojis.rust));
+
+        let confirmed = !confirm_each || Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Start session {}/{}?", i, sessions))
+            .default(true)
+            .interact()
+            .unwrap_or(false);
+
+        // Work period, unless you've called it a day
+        let end = if confirmed {
+            run_work_session(work, task_desc, settings, Some((i, sessions)), emojis, motivations)
+        } else {
+            WorkEnd::StopForToday
+        };
+        if end == WorkEnd::StopForToday {
+            println!("\n{} Schedule ended early: {} of {} sessions completed. {}",
+                     random_from(&emojis.success),
+                     (i - 1).to_string().bright_yellow(),
+                     sessions.to_string().bright_yellow(),
+                     rust_emoji);
+            if settings.eod_summary {
+                report::print_day_summary(settings.daily_goal, &settings.unit);
+            }
+            return;
+        }
+        worked += end.minutes();
+
+        // Determine break type
+        if i < sessions && long_break_after.is_some_and(|threshold| worked >= threshold) {
+            println!("\n{} {} minutes of work since your last long break, time for a longer one! {}",
+                     random_from(&emojis.success),
+                     worked.to_string().bright_yellow(),
+                     rust_emoji);
+            worked = 0;
+            run_break(long_break, true, settings, emojis, motivations);
+        } else if i < sessions {
+            // A skipped short break simply moves on to the next session
+            run_break(short_break, false, settings, emojis, motivations);
+        } else {
+            if settings.motivation {
+                println!("\n{} All sessions completed! Time for a well-deserved long break! {}",
+                         random_from(&emojis.success),
+                         rust_emoji);
+            }
+            run_break(long_break, true, settings, emojis, motivations);
+            sound::play_schedule_complete_sound(&settings.sound);
+
+            if settings.motivation {
+                println!("\n{} Great job completing all {} {}! {}",
+                         random_from(&emojis.success),
+                         sessions.to_string().bright_yellow(),
+                         settings.unit.plural_title(),
+                         rust_emoji);
+            }
+            if settings.eod_summary {
+                report::print_day_summary(settings.daily_goal, &settings.unit);
+            }
+        }
+    }
+}
+
+/// Run a fancy timer for `seconds` with progress bar and motivational messages.
+/// Pressing any of `stop_keys` ends the timer early.
+fn run_fancy_timer(seconds: u64, timer_type: &str, description: &str, settings: &Settings,
+                 stop_keys: &[char], emoji_set: &[&'static str], motivation_set: &[&'static str]) -> TimerEnd {
+    let clock = SystemClock;
+    let total_seconds = seconds;
+    let start_time = clock.now();
+
+    // Before the full-screen view takes over, so any instructions stay readable
+    check_notifications_once(settings);
+
+    let tui = if settings.tui { tui::Tui::new(timer_type, description) } else { None };
+
+    let mut listen = stop_keys.to_vec();
+    listen.extend([ADD_TIME_KEY, REMOVE_TIME_KEY]);
+    if settings.blind {
+        listen.push(PEEK_KEY);
+    }
+    if tui.is_some() {
+        listen.extend([tui::PAUSE_KEY, tui::SKIP_KEY, tui::QUIT_KEY]);
+    }
+    let keys = Some(KeyListener::new());
+    let mut fifo = settings.status_fifo.as_deref().map(StatusFifo::new);
+    let mut current = CurrentTimer::new(timer_type, description);
+    // A progress bar would give the game away in blind mode
+    let progress = settings.progress.as_ref().filter(|_| !settings.blind);
+    let mut status = StatusLine::new(progress.map(|style| (total_seconds, style)));
+    let mut peek_seconds: u64 = 0;
+    // Seconds since the status was last drawn; starts due so the first second shows
+    let mut since_draw = settings.refresh_seconds;
+
+    // Quarters of the session passed so far, and the latest one's note for the full-screen view
+    let mut milestones_passed = 0;
+    let mut milestone_note: Option<(String, u64)> = None;
+    let marks_milestones = settings.milestones && !timer_type.contains("Break");
+
+    // With --pin-emoji, the same emoji for the whole timer, shown on the status line too
+    let pinned = match &settings.pin_emoji {
+        emoji::EmojiPin::Off => None,
+        emoji::EmojiPin::PerTimer => Some(random_from(emoji_set).to_string()),
+        emoji::EmojiPin::Fixed(emoji) => Some(emoji.clone()),
+    };
+
+    // Warn once as the end nears; sessions shorter than the warning never get one
+    let mut warned = settings.warn_before == 0 || total_seconds <= settings.warn_before
+        || timer_type.contains("Break");
+
+    // Work sessions only, and not while presenting
+    let mut progress_popup = (settings.progress_notification && settings.notify
+        && !status::presenting() && !timer_type.contains("Break"))
+        .then(ProgressNotification::new);
+
+    // Spoken time checks fall on whole multiples of the interval before the end
+    let speak_step = settings.speak_interval.map(|minutes| minutes * 60);
+    let mut spoken_block = speak_step.map_or(0, |step| total_seconds.div_ceil(step));
+
+    let mut countdown = Countdown::new(total_seconds, clock.now());
+    let mut paused = false;
+    loop {
+        // Hold the countdown while the battery runs low; the end time shifts accordingly
+        let low_battery = settings.pause_on_low_battery
+            .and_then(|threshold| battery::read().filter(|battery| battery.is_low(threshold)))
+            .map(|battery| format!("⏸ Paused: battery at {}%, plug in to resume", battery.percent));
+
+        countdown.tick(clock.now(), low_battery.is_some() || paused);
+        let (remaining, total_seconds) = (countdown.remaining, countdown.total);
+        if remaining == 0 {
+            break;
+        }
+
+        // Mark each quarter as it passes, without stopping the clock
+        // (jumping ahead with - can pass several at once; only the latest is marked)
+        let reached = ((total_seconds - remaining) * 4 / total_seconds).min(3);
+        if marks_milestones && reached > milestones_passed {
+            milestones_passed = reached;
+            let emoji = pinned.as_deref().unwrap_or_else(|| random_from(emoji_set));
+            let mut note = format!("{} {}% done", emoji, milestones_passed * 25);
+            if settings.motivation {
+                note = format!("{}: {}", note, random_from(motivation_set));
+            }
+
+            if tui.is_some() {
+                milestone_note = Some((note, MILESTONE_NOTE_SECONDS));
+            } else {
+                println!("\r{}\x1b[K", note.bright_magenta());
+                since_draw = settings.refresh_seconds;
+            }
+            sound::play_milestone_sound(&settings.sound);
+        }
+
+        // Adding time after the warning doesn't bring it back
+        if !warned && remaining <= settings.warn_before && remaining > 0 {
+            warned = true;
+            let title = format!("{} left", format_time_left(remaining));
+            if !show_notification(&title, description, settings) && tui.is_none() {
+                println!("\r{}\x1b[K", format!("⏳ {}: {}", title, description).yellow());
+                since_draw = settings.refresh_seconds;
+            }
+        }
+
+        // Jumping ahead past several marks only says the latest; adding time says nothing
+        if let Some(step) = speak_step {
+            let block = remaining.div_ceil(step);
+            if block < spoken_block {
+                let minutes = block * step / 60;
+                let text = format!("{} minute{} remaining", minutes, if minutes == 1 { "" } else { "s" });
+                sound::speak(&text, &settings.sound);
+            }
+            spoken_block = block;
+        }
+
+        if let Some(popup) = &mut progress_popup {
+            popup.update(description, total_seconds - remaining, total_seconds);
+        }
+
+        // Every minute (or at specific intervals), show a motivational message
+        // if remaining % 60 == 0 && remaining > 0 && remaining < total_seconds {
+            // println!("\n{} {}",
+                     // random_from(emoji_set),
+                     // random_from(motivation_set).bright_green());
+        // }
+
+        // Calculate elapsed time and estimated end time
+        let elapsed = clock.now().signed_duration_since(start_time);
+        let elapsed_secs = elapsed.num_seconds() as u64;
+        let end_time = clock.now() + chrono::Duration::seconds(remaining as i64);
+        let eta = format_eta(end_time, &clock, settings.round_eta);
+
+        // Print current status every `--refresh` seconds, keeping the numbers out of sight in blind mode
+        let hidden = settings.blind && peek_seconds == 0;
+        peek_seconds = peek_seconds.saturating_sub(1);
+        since_draw += 1;
+        if since_draw >= settings.refresh_seconds {
+            since_draw = 0;
+
+            if let Some(tui) = &tui {
+                let note = low_battery.as_deref()
+                    .or(paused.then_some("⏸ Paused, press p to resume"))
+                    .or(milestone_note.as_ref().map(|(note, _)| note.as_str()));
+                tui.draw((!hidden).then_some(remaining), total_seconds, &eta, note);
+            } else if let Some(message) = &low_battery {
+                print!("\r{}  ", message.bright_red());
+                io::stdout().flush().unwrap();
+            } else if hidden {
+                status.show_hidden(description);
+            } else {
+                // A pinned emoji leads the line, then the progress ring
+                let clock = [pinned.as_deref(), settings.spinner.glyph(total_seconds - remaining, total_seconds)]
+                    .into_iter()
+                    .flatten()
+                    .chain([eta.as_str()])
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                status.show(&clock, remaining, description);
+            }
+            if low_battery.is_none()
+                && let Some(fifo) = &mut fifo {
+                fifo.write(&format!("{} {:02}:{:02}", timer_type, remaining / 60, remaining % 60));
+            }
+        }
+
+        current.update(remaining, total_seconds, paused || low_battery.is_some());
+
+        if let Some((_, seconds)) = &mut milestone_note {
+            *seconds -= 1;
+            if *seconds == 0 {
+                milestone_note = None;
+            }
+        }
+
+        // Wait one second, checking for key presses along the way.
+        // Anything pressed is shown straight away rather than at the next refresh.
+        let key = wait_one_second(&keys, &listen);
+        if key.is_some() {
+            since_draw = settings.refresh_seconds;
+        }
+        match key {
+            Some(ADD_TIME_KEY) => {
+                countdown.add(ADJUST_SECONDS);
+                status.set_total(countdown.total);
+            },
+            Some(REMOVE_TIME_KEY) => {
+                countdown.cut(ADJUST_SECONDS);
+                status.set_total(countdown.total);
+            },
+            Some(PEEK_KEY) if settings.blind => peek_seconds = PEEK_SECONDS,
+            Some(tui::PAUSE_KEY) if tui.is_some() => paused = !paused,
+            Some(tui::QUIT_KEY) if tui.is_some() => {
+                // Same as Ctrl+C: leave the full screen and exit, logging only how far the session got
+                log_interrupted_work();
+                drop(tui);
+                drop(keys);
+                drop(current);
+                println!();
+                events::interrupted();
+                std::process::exit(0);
+            },
+            Some(_) => {
+                if tui.is_none() {
+                    println!();
+                }
+                return TimerEnd::Stopped;
+            },
+            None => (),
+        }
+    }
+
+    drop(tui);
+    println!("");
+    // println!("\n{} {} completed! {} {}",
+             // random_from(emoji_set),
+             // timer_type.bright_yellow(),
+             // description.bright_green(),
+             // random_from(&["Great job!", "Well done!", "Excellent!", "Fantastic!", "Amazing!"]));
+
+    // Leave status bars showing a finished timer
+    if let Some(fifo) = &mut fifo {
+        fifo.write(&format!("{} done", timer_type));
+    }
+
+    TimerEnd::Finished { seconds: countdown.total }
+}
+
+/// Ask a yes/no question (defaulting to yes), answering no if there's no reply
+/// within `timeout_minutes`. Falls back to a regular prompt without a timeout
+/// when keys can't be read one at a time, or when the timeout is 0.
+fn confirm_with_timeout(prompt: &str, timeout_minutes: u64) -> bool {
+    let keys = KeyListener::new();
+
+    if timeout_minutes == 0 || !keys.is_active() {
+        drop(keys);
+        return Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .default(true)
+            .interact()
+            .unwrap_or(false);
+    }
+
+    print!("{} {} {} ", "?".yellow(), prompt.bold(), "(Y/n)".dimmed());
+    io::stdout().flush().unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(timeout_minutes * 60);
+    loop {
+        match keys.poll() {
+            Some('y' | 'Y' | '\n' | '\r') => {
+                println!("{}", "yes".green());
+                return true;
+            },
+            Some('n' | 'N') => {
+                println!("{}", "no".green());
+                return false;
+            },
+            _ => (),
+        }
+
+        if Instant::now() >= deadline {
+            println!("\n{}", format!("No answer for {} min, exiting.", timeout_minutes).dimmed());
+            return false;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// Sleep for a second while watching for stop keys; returns the one pressed, if any
+fn wait_one_second(keys: &Option<KeyListener>, stop_keys: &[char]) -> Option<char> {
+    match keys {
+        Some(keys) => {
+            for _ in 0..10 {
+                if let Some(key) = keys.poll().filter(|key| stop_keys.contains(key)) {
+                    return Some(key);
+                }
+                thread::sleep(Duration::from_millis(100));
+            }
+        },
+        None => thread::sleep(Duration::from_secs(1)),
+    }
+
+    None
+}
+
+/// The status line: a clock time, a MM:SS counter and the description.
+/// Redrawn in place on a terminal; when output is redirected, printed as
+/// a plain line once per minute so logs don't fill up with redraws.
+struct StatusLine {
+    tty: bool,
+    last_minute: Option<u64>,
+    /// Progress bar drawn instead of the plain line, for countdowns with `--progress-bar`
+    bar: Option<ProgressBar>,
+}
+
+impl StatusLine {
+    /// A status line; pass the total seconds and a style to count down with a progress bar
+    fn new(progress: Option<(u64, &ProgressStyle)>) -> StatusLine {
+        let tty = io::stdout().is_terminal();
+        let bar = progress.filter(|_| tty).map(|(total, style)| {
+            ProgressBar::with_draw_target(Some(total), ProgressDrawTarget::stdout())
+                .with_style(style.clone())
+        });
+
+        StatusLine { tty, last_minute: None, bar }
+    }
+
+    /// Change the countdown length after time is added or taken off
+    fn set_total(&self, total: u64) {
+        if let Some(bar) = &self.bar {
+            bar.set_length(total);
+        }
+    }
+
+    /// Show that a timer is running without saying how long is left
+    fn show_hidden(&mut self, description: &str) {
+        if self.tty {
+            let label = "⏳ working… (space to peek)";
+            let description = fit_to_terminal(description, display_width(label) + 3);
+            print!("\r{} | {}\x1b[K",
+                   label.dimmed(),
+                   description.green());
+            io::stdout().flush().unwrap();
+        }
+    }
+
+    fn show(&mut self, clock: &str, seconds: u64, description: &str) {
+        let counter = format!("{:02}:{:02}", seconds / 60, seconds % 60);
+
+        if let Some(bar) = &self.bar {
+            bar.set_message(format!("{} | {} | {}",
+                                    clock.bright_cyan(),
+                                    counter.bold().yellow(),
+                                    description.green()));
+            bar.set_position(bar.length().unwrap_or(0).saturating_sub(seconds));
+        } else if self.tty {
+            // Measured on every redraw, so resizing the window mid-session is picked up
+            let description = fit_to_terminal(description, display_width(clock) + counter.len() + 6 + 2);
+            print!("\r{} | {} | {}  ",
+                   clock.bright_cyan(),
+                   counter.bold().yellow(),
+                   description.green());
+            io::stdout().flush().unwrap();
+        } else if self.last_minute != Some(seconds.div_ceil(60)) {
+            // Minutes rounded up, so the full length prints once at the start, then each minute after
+            self.last_minute = Some(seconds.div_ceil(60));
+            println!("{} | {} | {}", clock, counter, description);
+        }
+    }
+}
+
+/// Cut `text` short with an ellipsis so that, after `used` columns, it still fits on
+/// one row of the terminal; a line that wraps can't be redrawn in place with `\r`.
+/// Left alone when the width isn't known.
+fn fit_to_terminal(text: &str, used: usize) -> String {
+    let Some(width) = terminal_width() else {
+        return text.to_string();
+    };
+    // Stay off the last column, where some terminals wrap early
+    let room = width.saturating_sub(used + 1);
+    if display_width(text) <= room {
+        return text.to_string();
+    }
+    if room == 0 {
+        return String::new();
+    }
+
+    let mut fitted = String::new();
+    let mut taken = 0;
+    for c in text.chars() {
+        let w = char_width(c);
+        if taken + w > room - 1 {
+            break;
+        }
+        fitted.push(c);
+        taken += w;
+    }
+    fitted.push('…');
+    fitted
+}
+
+/// Columns `text` takes up on a terminal, counting emoji and other wide characters as two
+fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// Close enough for status lines: CJK, Hangul, fullwidth forms and emoji are wide,
+/// zero-width joiners and variation selectors take no room
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x200B..=0x200F | 0xFE00..=0xFE0F => 0,
+        0x1100..=0x115F | 0x2E80..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF
+        | 0xFF00..=0xFF60 | 0xFFE0..=0xFFE6 | 0x1F300..=0x1FAFF | 0x20000..=0x3FFFD => 2,
+        // Emoji in older blocks that terminals draw two columns wide
+        0x231A..=0x231B | 0x23E9..=0x23F3 | 0x25FD..=0x25FE | 0x2614..=0x2615 | 0x26A1 | 0x2705 | 0x274C => 2,
+        _ => 1,
+    }
+}
+
+/// Columns in the terminal stdout is attached to, if it is one
+#[cfg(unix)]
+fn terminal_width() -> Option<usize> {
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    let ok = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } == 0;
+    (ok && size.ws_col > 0).then_some(size.ws_col as usize)
+}
+
+/// Elsewhere, trust `COLUMNS` if the shell exported it
+#[cfg(not(unix))]
+fn terminal_width() -> Option<usize> {
+    std::env::var("COLUMNS").ok()?.parse().ok().filter(|&columns| columns > 0)
+}
+
+/// Parse a `--rate`, which can't be negative
+fn parse_rate(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(rate) if rate >= 0.0 && rate.is_finite() => Ok(rate),
+        _ => Err(format!("expected an hourly rate like 80 or 47.50, got {:?}", value)),
+    }
+}
+
+/// Parse an `invoice --month` into the first day of that month
+fn parse_month(value: &str) -> Result<chrono::NaiveDate, String> {
+    chrono::NaiveDate::parse_from_str(&format!("{}-01", value.trim()), "%Y-%m-%d")
+        .map_err(|_| format!("expected a month like 2024-03, got {:?}", value))
+}
+
+/// Parse a `suggest-break --ratio`, a positive fraction like 0.2
+fn parse_ratio(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(ratio) if ratio > 0.0 && ratio <= 1.0 => Ok(ratio),
+        _ => Err(format!("expected a number above 0 and up to 1, like 0.2, got {:?}", value)),
+    }
+}
+
+/// A break to go with `work` minutes of work, rounded to the nearest whole
+/// minute but never shorter than `MIN_SUGGESTED_BREAK`
+fn suggested_break(work: u64, ratio: f64) -> u64 {
+    ((work as f64 * ratio).round() as u64).max(MIN_SUGGESTED_BREAK)
+}
+
+/// Parse a `--start-at` time of day
+fn parse_clock_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .map_err(|_| format!("expected a time like 14:30, got {:?}", value))
+}
+
+/// Count down to the next `time` of day before a deferred start. Ctrl+C exits
+/// while waiting, so nothing is logged. Returns false if `time` doesn't exist
+/// (skipped by a DST change).
+fn wait_until(time: NaiveTime, task_desc: &str) -> bool {
+    let now = Local::now();
+    let mut date = now.date_naive();
+    if time <= now.time() {
+        date = date.succ_opt().unwrap_or(date);
+        println!("{}", format!("{} has already passed today, starting tomorrow.", time.format("%H:%M")).dimmed());
+    }
+
+    let Some(target) = Local.from_local_datetime(&date.and_time(time)).earliest() else {
+        println!("❌ {} doesn't exist on {} because of a clock change", time.format("%H:%M"), date);
+        return false;
+    };
+
+    let description = format!("waiting to start: {}", task_desc);
+    let mut status = StatusLine::new(None);
+    loop {
+        let millis = target.signed_duration_since(Local::now()).num_milliseconds();
+        if millis <= 0 {
+            break;
+        }
+        status.show(&target.format("%H:%M").to_string(), (millis as u64).div_ceil(1000), &description);
+        thread::sleep(Duration::from_millis(millis.min(1000) as u64));
+    }
+
+    println!();
+    true
+}
+
+/// Where timers read the time from, so tests can stand in a clock of their own
+trait Clock {
+    type Tz: TimeZone;
+
+    fn now(&self) -> DateTime<Self::Tz>;
+}
 
-        // Create filename based on current date (YYYYMMDD.txt)
-        let now = Local::now();
-        let filename = format!("{}.txt", now.format("%Y%m%d"));
-        let file_path = completed_dir.join(filename);
+/// The machine's local time
+struct SystemClock;
 
-        // Format the log entry: "HH:MM:SS | task_desc"
-        let log_entry = format!("{} | {}\n", now.format("%H:%M:%S"), task_desc);
+impl Clock for SystemClock {
+    type Tz = Local;
 
-        // Append to the file
-        if let Ok(mut file) = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(file_path) {
-            let _ = file.write_all(log_entry.as_bytes());
-        }
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
     }
 }
 
-/// Run a work session with timer and motivational messages
-fn run_work_session(minutes: u64, task_desc: &str, emojis: &Emojis, motivations: &Motivations) {
-    let work_emoji = random_from(&emojis.work);
-    let rust_emoji = random_from(&emojis.rust);
+/// Format the projected end time, optionally rounded to the nearest minute.
+/// The end time is an instant shown as local wall-clock time, so across a DST
+/// change it jumps by the shift, matching the time the session is logged at.
+/// When the end falls on the other side of a change from `clock`'s now, its UTC offset is shown too.
+fn format_eta<C: Clock>(end_time: DateTime<C::Tz>, clock: &C, round: bool) -> String
+where <C::Tz as TimeZone>::Offset: std::fmt::Display {
+    let offset = end_time.offset().fix();
+    let eta = if round {
+        // Adding half a minute before truncating the seconds rounds to the nearest minute
+        (end_time + chrono::Duration::seconds(30)).format("%H:%M").to_string()
+    } else {
+        end_time.format("%H:%M:%S").to_string()
+    };
 
-    // println!("\n{} {} {}", work_emoji, random_from(&motivations.start_work).bright_green(), rust_emoji);
-    // println!("{} Starting {} minute Pomodoro for: {}\n",
-             // work_emoji,
-             // minutes.to_string().bright_yellow(),
-             // task_desc.bright_cyan());
+    if offset != clock.now().offset().fix() {
+        format!("{} {}", eta, offset)
+    } else {
+        eta
+    }
+}
 
-    run_fancy_timer(minutes, "Pomodoro", task_desc, &emojis.work, &motivations.during_work);
+/// Display a desktop notification and play alert sound
+fn notify(title: &str, message: &str, settings: &Settings) {
+    // Stay quiet while screen sharing
+    if status::presenting() {
+        return;
+    }
 
-    // Log the completed task
-    log_completed_task(task_desc);
+    if !show_notification(title, message, settings) {
+        println!("\n{}: {}", title.bright_yellow(), message.bright_green()); // Fallback if notifications fail or are off
+    }
 
-    // println!("\n{} {} {}",
-             // random_from(&emojis.success),
-             // random_from(&motivations.end_work).bright_green(),
-             // rust_emoji);
+    // Play alert sound
+    sound::play_alert_sound(&settings.sound);
 
-    // This will play the alert sound
-    notify("Pomodoro completed!",
-           &format!("{} You completed a {} minute pomodoro for: {}",
-                   random_from(&emojis.success),
-                   minutes,
-                   task_desc));
+    if settings.haptic {
+        haptic::rumble();
+    }
 }
 
-/// Run a break session with timer and motivational messages
-fn run_break(minutes: u64, is_long: bool, emojis: &Emojis, motivations: &Motivations) {
-    let break_type = if is_long { "long" } else { "short" };
-    let break_emojis = if is_long { &emojis.break_long } else { &emojis.break_short };
-    let break_emoji = random_from(break_emojis);
-    let rust_emoji = random_from(&emojis.rust);
-
-    // println!("\n{} {} {}", break_emoji, random_from(&motivations.start_break).bright_blue(), rust_emoji);
-    // println!("{} Starting {} minute {} break\n",
-             // break_emoji,
-             // minutes.to_string().bright_yellow(),
-             // break_type.bright_magenta());
 
-    run_fancy_timer(minutes, &format!("{} Break", if is_long { "Long" } else { "Short" }),
-                  "Time to relax", break_emojis, &motivations.start_break);
 
-    // println!("\n{} {} {}",
-             // random_from(&emojis.success),
-             // random_from(&motivations.end_break).bright_green(),
-             // rust_emoji);
+/// Show a desktop notification without any sound, returning whether it was shown
+fn show_notification(title: &str, message: &str, settings: &Settings) -> bool {
+    settings.notify && !status::presenting() && notify_rust::Notification::new()
+        .summary(title)
+        .body(message)
+        .show()
+        .is_ok()
+}
 
-    notify("Break ended!",
-           &format!("{} Your {} minute break has ended",
-                   random_from(&emojis.success),
-                   minutes));
+/// Describe a short stretch of time, e.g. "1 minute" or "90 seconds"
+fn format_time_left(seconds: u64) -> String {
+    match seconds {
+        60 => "1 minute".to_string(),
+        s if s % 60 == 0 => format!("{} minutes", s / 60),
+        1 => "1 second".to_string(),
+        s => format!("{} seconds", s),
+    }
 }
 
-/// Run a schedule of pomodoro sessions with breaks
-fn run_schedule(sessions: u32, work: u64, short_break: u64, long_break: u64,
-               task_desc: &str, emojis: &Emojis, motivations: &Motivations) {
-    let rust_emoji = random_from(&emojis.rust);
+/// File in the data dir recording that notifications were tested
+const NOTIFY_CHECKED_FILE: &str = "notify_checked";
 
-    println!("{} Scheduling {} work sessions ({} min) with short breaks ({} min) and a long break ({} min) {}",
-             random_from(&emojis.work),
-             sessions.to_string().bright_yellow(),
-             work.to_string().bright_green(),
-             short_break.to_string().bright_blue(),
-             long_break.to_string().bright_magenta(),
-             rust_emoji);
+/// On the first timer ever, send a test notification and explain how to allow
+/// notifications if it fails. Whatever happens, this is only done once.
+fn check_notifications_once(settings: &Settings) {
+    if !settings.notify || status::presenting() {
+        return;
+    }
+    let Some(dir) = history::data_dir() else {
+        return;
+    };
+    let marker = dir.join(NOTIFY_CHECKED_FILE);
+    if marker.exists() {
+        return;
+    }
 
-    for i in 1..=sessions {
-        println!("\n{} {} === Session {}/{} === {} {}",
-                 random_from(&emojis.work),
-                 "🔄".bright_yellow(),
-                 i.to_string().bright_yellow(),
-                 sessions.to_string().bright_yellow(),
-                 "🔄".bright_yellow(),
-                 random_from(&emojis.rust));
+    let result = notify_rust::Notification::new()
+        .summary("pomodoro_rs notifications are on")
+        .body("You'll get one like this when each timer ends.")
+        .show();
 
-        // Work period
-        run_work_session(work, task_desc, emojis, motivations);
+    if let Err(e) = result {
+        println!("{} {}", "⚠️ Desktop notifications don't seem to work:".yellow(), e);
+        println!("{}", notification_help().yellow());
+        println!("{}\n", "Messages will be printed here instead. Use --no-notify to stop trying.".dimmed());
+    }
 
-        // Determine break type
-        if i < sessions {
-            run_break(short_break, false, emojis, motivations);
-        } else {
-            println!("\n{} All sessions completed! Time for a well-deserved long break! {}",
-                     random_from(&emojis.success),
-                     rust_emoji);
-            run_break(long_break, true, emojis, motivations);
+    if std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&marker, "")).is_err() {
+        eprintln!("⚠️ Couldn't save {:?}, so this check will run again", marker);
+    }
+}
 
-            println!("\n{} Great job completing all {} Pomodoros! {}",
-                     random_from(&emojis.success),
-                     sessions.to_string().bright_yellow(),
-                     rust_emoji);
-        }
+/// How to let notifications through on this platform
+fn notification_help() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "Open System Settings → Notifications, pick your terminal app (Terminal, iTerm…) and turn on Allow Notifications."
+    } else if cfg!(windows) {
+        "Open Settings → System → Notifications, turn notifications on for your terminal (Windows Terminal or PowerShell) and check that Do not disturb is off."
+    } else {
+        "Make sure a notification daemon is running (your desktop's own, or one like dunst or mako) and that Do not disturb is off."
     }
 }
 
-/// Run a fancy timer with progress bar and motivational messages
-fn run_fancy_timer(minutes: u64, _timer_type: &str, description: &str,
-                 emoji_set: &[&'static str], motivation_set: &[&'static str]) {
-    let total_seconds = minutes * 60;
-    let start_time = Local::now();
+/// Ask for a whole number of minutes (or sessions) above zero
+fn ask_count(prompt: &str, default: u64) -> Option<u64> {
+    dialoguer::Input::<u64>::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .default(default)
+        .validate_with(|n: &u64| if *n > 0 { Ok(()) } else { Err("must be more than 0") })
+        .interact_text()
+        .ok()
+}
 
-    for remaining in (0..total_seconds).rev() {
+fn ask_yes_no(prompt: &str, default: bool) -> bool {
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .default(default)
+        .interact()
+        .unwrap_or(false)
+}
 
-        // Calculate remaining minutes and seconds
-        let mins = remaining / 60;
-        let secs = remaining % 60;
+/// Interactive first-time setup: pick durations and a sound theme, try the sound and
+/// a notification, save the answers to the global config, and offer to install
+fn run_setup(settings: &Settings) {
+    if !io::stdin().is_terminal() {
+        println!("❌ Setup asks questions, so it needs to run in a terminal");
+        return;
+    }
+    let Some(path) = config::global_config_path() else {
+        println!("❌ Could not determine the config directory");
+        return;
+    };
 
-        // Every minute (or at specific intervals), show a motivational message
-        // if remaining % 60 == 0 && remaining > 0 && remaining < total_seconds {
-            // println!("\n{} {}",
-                     // random_from(emoji_set),
-                     // random_from(motivation_set).bright_green());
-        // }
+    println!("🦀 Let's set up pomodoro_rs! Press Enter to keep the value in brackets.\n");
 
-        // Calculate elapsed time and estimated end time
-        let elapsed = Local::now().signed_duration_since(start_time);
-        let elapsed_secs = elapsed.num_seconds() as u64;
-        let end_time = Local::now() + chrono::Duration::seconds(remaining as i64);
+    let (Some(work), Some(short_break), Some(long_break), Some(sessions)) = (
+        ask_count("Work session length (minutes)", settings.work_minutes),
+        ask_count("Short break length (minutes)", settings.short_break_minutes),
+        ask_count("Long break length (minutes)", settings.long_break_minutes),
+        ask_count("Work sessions in a schedule", settings.sessions as u64),
+    ) else {
+        println!("❌ Setup cancelled, nothing was saved");
+        return;
+    };
 
-        // Print current status
-        print!("\r{} | {} | {}  ",
-               end_time.format("%H:%M").to_string().bright_cyan(),
-               format!("{:02}:{:02}", mins, secs).bold().yellow(),
-               description.green());
-        io::stdout().flush().unwrap();
+    let themes = sound::SoundTheme::value_variants();
+    let current = themes.iter().position(|theme| *theme == settings.sound.theme).unwrap_or(0);
+    let names: Vec<String> = themes.iter()
+        .filter_map(|theme| theme.to_possible_value().map(|value| value.get_name().to_string()))
+        .collect();
+    let Ok(choice) = dialoguer::Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Sound theme")
+        .items(&names)
+        .default(current)
+        .interact() else {
+        println!("❌ Setup cancelled, nothing was saved");
+        return;
+    };
 
-        // Wait one second
-        thread::sleep(Duration::from_secs(1));
+    let sound = sound::SoundSettings { theme: themes[choice], enabled: true, ..settings.sound.clone() };
+    if ask_yes_no("Play the alert sound now?", true) {
+        preview_sound(&sound);
     }
 
-    println!("");
-    // println!("\n{} {} completed! {} {}",
-             // random_from(emoji_set),
-             // timer_type.bright_yellow(),
-             // description.bright_green(),
-             // random_from(&["Great job!", "Well done!", "Excellent!", "Fantastic!", "Amazing!"]));
-}
+    if ask_yes_no("Send a test notification?", true) {
+        match notify_rust::Notification::new()
+            .summary("pomodoro_rs notifications are on")
+            .body("You'll get one like this when each timer ends.")
+            .show() {
+            Ok(_) => println!("✅ Notification sent. If nothing appeared, check your Do not disturb settings."),
+            Err(e) => {
+                println!("{} {}", "⚠️ Desktop notifications don't seem to work:".yellow(), e);
+                println!("{}", notification_help().yellow());
+            },
+        }
+    }
 
-/// Display a desktop notification and play alert sound
-fn notify(title: &str, message: &str) {
-    // Show desktop notification
-    match notify_rust::Notification::new()
-        .summary(title)
-        .body(message)
-        .show() {
-            Ok(_) => (),
-            Err(_) => println!("\n{}: {}", title.bright_yellow(), message.bright_green()), // Fallback if notifications fail
+    println!();
+    if ask_yes_no(&format!("Save these settings to {:?}?", path), true) {
+        let values = [
+            ("work", config::Value::Int(work as i64)),
+            ("short_break", config::Value::Int(short_break as i64)),
+            ("long_break", config::Value::Int(long_break as i64)),
+            ("sessions", config::Value::Int(sessions as i64)),
+            ("sound_theme", config::Value::Str(names[choice].clone())),
+        ];
+        match config::set_values(&path, &values) {
+            Ok(()) => println!("✅ Saved. Edit the file any time to change more settings."),
+            Err(e) => println!("❌ Failed to save {:?}: {}", path, e),
         }
+    }
 
-    // Play alert sound
-}
+    if ask_yes_no("Install pomodoro_rs to your PATH?", false) {
+        install_to_path(&InstallArgs::default());
+    }
 
+    println!("\n🍅 All set! Run `pomodoro_rs` to start your first session.");
+}
 
+/// Comment line install writes above the PATH line it adds to a shell profile
+const INSTALLER_MARKER: &str = "# Added by pomodoro_rs installer";
+
+/// Where install puts the binary: `bin_dir` if given, made absolute, else ~/.local/bin
+fn install_dir(home: &Path, bin_dir: Option<&PathBuf>) -> PathBuf {
+    match bin_dir {
+        // The directory ends up in PATH, so a relative one won't do
+        Some(dir) if dir.is_relative() => std::env::current_dir()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .join(dir),
+        Some(dir) => dir.clone(),
+        None => home.join(".local").join("bin"),
+    }
+}
 
-/// Install the binary to user's PATH
-fn install_to_path() {
-    println!("🦀 Let's install pomodoro_rs to your PATH!");
+/// Install the binary to user's PATH. With `dry_run`, only say what would be built,
+/// copied and written, asking nothing and changing nothing.
+fn install_to_path(options: &InstallArgs) {
+    let dry_run = options.dry_run;
+    if dry_run {
+        println!("🦀 Dry run: here's what installing pomodoro_rs would do. Nothing will be changed.");
+    } else {
+        println!("🦀 Let's install pomodoro_rs to your PATH!");
+    }
 
     // First build the release version
-    println!("Building release version...");
-    let build_result = Command::new("cargo")
-        .args(["build", "--release"])
-        .status();
-
-    if let Err(e) = build_result {
-        println!("❌ Failed to build: {}", e);
-        return;
-    }
+    if dry_run {
+        println!("Would run: cargo build --release");
+    } else {
+        println!("Building release version...");
+        let build_result = Command::new("cargo")
+            .args(["build", "--release"])
+            .status();
 
-    // Create assets directory in the target
-    println!("Setting up assets directory...");
-    let target_assets_dir = PathBuf::from("target/release/assets");
-    if !target_assets_dir.exists() {
-        if let Err(e) = std::fs::create_dir_all(&target_assets_dir) {
-            println!("⚠️ Warning: Failed to create assets directory: {}", e);
+        if let Err(e) = build_result {
+            println!("❌ Failed to build: {}", e);
+            return;
         }
     }
 
@@ -474,10 +3232,18 @@ fn install_to_path() {
         }
     };
 
-    let target_dir = PathBuf::from(&home).join(".local").join("bin");
+    let target_dir = install_dir(&home, options.bin_dir.as_ref());
+    // How the directory is written in a shell profile
+    let path_dir = if target_dir == home.join(".local").join("bin") {
+        String::from("$HOME/.local/bin")
+    } else {
+        target_dir.to_string_lossy().to_string()
+    };
 
     // Create target directory if it doesn't exist
-    if !target_dir.exists() {
+    if dry_run && !target_dir.exists() {
+        println!("Would create directory: {:?}", target_dir);
+    } else if !target_dir.exists() {
         println!("Creating directory: {:?}", target_dir);
         if let Err(e) = std::fs::create_dir_all(&target_dir) {
             println!("❌ Failed to create directory: {}", e);
@@ -494,37 +3260,35 @@ fn install_to_path() {
 
     let dest_path = target_dir.join("pomodoro_rs");
 
-    println!("Copying from {:?} to {:?}", binary_path, dest_path);
-
-    if let Err(e) = std::fs::copy(&binary_path, &dest_path) {
-        println!("❌ Failed to copy binary: {}", e);
-        return;
-    }
+    if dry_run {
+        let replace = if dest_path.exists() { ", replacing the one there" } else { "" };
+        println!("Would copy {:?} to {:?}{}", binary_path, dest_path, replace);
+        #[cfg(unix)]
+        println!("Would make {:?} executable (mode 755)", dest_path);
+    } else {
+        println!("Copying from {:?} to {:?}", binary_path, dest_path);
 
-    // Create assets directory in the destination
-    let dest_assets_dir = target_dir.join("assets");
-    if !dest_assets_dir.exists() {
-        println!("Creating assets directory at: {:?}", dest_assets_dir);
-        if let Err(e) = std::fs::create_dir_all(&dest_assets_dir) {
-            println!("⚠️ Warning: Failed to create assets directory: {}", e);
+        if let Err(e) = std::fs::copy(&binary_path, &dest_path) {
+            println!("❌ Failed to copy binary: {}", e);
+            return;
         }
-    }
 
-    // Make it executable
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let metadata = std::fs::metadata(&dest_path).unwrap();
-        let mut perms = metadata.permissions();
-        perms.set_mode(0o755);
-        if let Err(e) = std::fs::set_permissions(&dest_path, perms) {
-            println!("❌ Failed to set permissions: {}", e);
-            return;
+        // Make it executable
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let metadata = std::fs::metadata(&dest_path).unwrap();
+            let mut perms = metadata.permissions();
+            perms.set_mode(0o755);
+            if let Err(e) = std::fs::set_permissions(&dest_path, perms) {
+                println!("❌ Failed to set permissions: {}", e);
+                return;
+            }
         }
-    }
 
-    println!("\n✅ Installation successful! 🦀");
-    println!("Binary installed to: {:?}", dest_path);
+        println!("\n✅ Installation successful! 🦀");
+        println!("Binary installed to: {:?}", dest_path);
+    }
 
     // Check if the installation directory is already in PATH
     let path_env = match std::env::var("PATH") {
@@ -532,8 +3296,12 @@ fn install_to_path() {
         Err(_) => {
             println!("\nMake sure {:?} is in your PATH.", target_dir);
             println!("You might need to add this to your shell profile:");
-            println!("  export PATH=\"$HOME/.local/bin:$PATH\"");
-            println!("\nNow you can run the command 'pomodoro_rs' from anywhere!");
+            println!("  export PATH=\"{}:$PATH\"", path_dir);
+            if dry_run {
+                println!("\nDry run: nothing was changed.");
+            } else {
+                println!("\nNow you can run the command 'pomodoro_rs' from anywhere!");
+            }
             return;
         }
     };
@@ -543,12 +3311,26 @@ fn install_to_path() {
 
     if path_entries.contains(&target_dir_str.as_ref()) {
         println!("\nGood news! {:?} is already in your PATH.", target_dir);
-        println!("You can run the command 'pomodoro_rs' from anywhere!");
+        if dry_run {
+            println!("No shell profile would be touched.");
+            println!("\nDry run: nothing was changed.");
+        } else {
+            println!("You can run the command 'pomodoro_rs' from anywhere!");
+        }
+        return;
+    }
+
+    if options.no_path_edit {
+        println!("\nLeaving your shell profile alone; add {:?} to your PATH yourself:", target_dir);
+        println!("  export PATH=\"{}:$PATH\"", path_dir);
+        if dry_run {
+            println!("\nDry run: nothing was changed.");
+        }
         return;
     }
 
     // Ask if the user wants to add it to their PATH
-    if !Confirm::with_theme(&ColorfulTheme::default())
+    if !dry_run && !options.yes && !Confirm::with_theme(&ColorfulTheme::default())
         .with_prompt("Would you like to add the installation directory to your PATH?")
         .default(true)
         .interact()
@@ -556,7 +3338,7 @@ fn install_to_path() {
 
         println!("\nYou'll need to manually add {:?} to your PATH.", target_dir);
         println!("Add this line to your shell profile:");
-        println!("  export PATH=\"$HOME/.local/bin:$PATH\"");
+        println!("  export PATH=\"{}:$PATH\"", path_dir);
         return;
     }
 
@@ -575,16 +3357,37 @@ fn install_to_path() {
             println!("\nCould not detect your shell profile file.");
             println!("Please manually add {:?} to your PATH.", target_dir);
             println!("Add this line to your shell profile:");
-            println!("  export PATH=\"$HOME/.local/bin:$PATH\"");
+            println!("  export PATH=\"{}:$PATH\"", path_dir);
+            if dry_run {
+                println!("\nDry run: nothing was changed.");
+            }
             return;
         }
     };
 
+    // Add the directory to PATH in the appropriate file
+    let path_line = if shell_basename == "fish" {
+        format!("set -x PATH {} $PATH\n", path_dir)
+    } else {
+        format!("export PATH=\"{}:$PATH\"\n", path_dir)
+    };
+
+    if dry_run {
+        println!("\nDetected shell: {}", shell_basename);
+        let action = if profile_file.exists() { "append to" } else { "create" };
+        let asking = if options.yes { "Without asking" } else { "After asking" };
+        println!("{}, would {} {:?}:", asking, action, profile_file);
+        println!("  {}", INSTALLER_MARKER);
+        println!("  {}", path_line.trim());
+        println!("\nDry run: nothing was changed.");
+        return;
+    }
+
     // Ask for confirmation since we're modifying a config file
     println!("\nDetected shell: {}", shell_basename);
     println!("Will add PATH entry to: {:?}", profile_file);
 
-    if !Confirm::with_theme(&ColorfulTheme::default())
+    if !options.yes && !Confirm::with_theme(&ColorfulTheme::default())
         .with_prompt(&format!("Proceed to modify {:?}?", profile_file))
         .default(true)
         .interact()
@@ -595,13 +3398,6 @@ fn install_to_path() {
         return;
     }
 
-    // Add the directory to PATH in the appropriate file
-    let path_line = if shell_basename == "fish" {
-        format!("set -x PATH $HOME/.local/bin $PATH\n")
-    } else {
-        format!("export PATH=\"$HOME/.local/bin:$PATH\"\n")
-    };
-
     let result = if profile_file.exists() {
         // Append to existing file
         std::fs::OpenOptions::new()
@@ -609,12 +3405,12 @@ fn install_to_path() {
             .append(true)
             .open(&profile_file)
             .and_then(|mut file| {
-                file.write_all(b"\n# Added by pomodoro_rs installer\n")?;
+                writeln!(file, "\n{}", INSTALLER_MARKER)?;
                 file.write_all(path_line.as_bytes())
             })
     } else {
         // Create new file
-        std::fs::write(&profile_file, format!("# Added by pomodoro_rs installer\n{}", path_line))
+        std::fs::write(&profile_file, format!("{}\n{}", INSTALLER_MARKER, path_line))
     };
 
     match result {
@@ -636,21 +3432,114 @@ fn install_to_path() {
     }
 }
 
+/// Remove what install put in place: the binary, optionally the assets next to it,
+/// and the marked PATH lines in any shell profile
+fn uninstall(yes: bool, bin_dir: Option<&PathBuf>, assets: bool) {
+    let home = match home_dir() {
+        Some(path) => path,
+        None => {
+            println!("❌ Could not determine your home directory");
+            return;
+        }
+    };
+
+    let target_dir = install_dir(&home, bin_dir);
+    let binary = target_dir.join("pomodoro_rs");
+    let assets_dir = target_dir.join("assets");
+    let profiles: Vec<PathBuf> = [
+        home.join(".bashrc"),
+        home.join(".zshrc"),
+        home.join(".config").join("fish").join("config.fish"),
+    ]
+    .into_iter()
+    .filter(|profile| {
+        std::fs::read_to_string(profile)
+            .map(|text| text.lines().any(|line| line.trim() == INSTALLER_MARKER))
+            .unwrap_or(false)
+    })
+    .collect();
+
+    let remove_binary = binary.exists();
+    let remove_assets = assets && assets_dir.is_dir();
+    if !remove_binary && !remove_assets && profiles.is_empty() {
+        println!("Nothing to uninstall: no binary at {:?} and no PATH line from the installer.", binary);
+        return;
+    }
+
+    println!("🦀 This will remove:");
+    if remove_binary {
+        println!("  {:?}", binary);
+    }
+    if remove_assets {
+        println!("  {:?} and everything in it", assets_dir);
+    }
+    for profile in &profiles {
+        println!("  the PATH line the installer added to {:?}", profile);
+    }
+
+    if !yes && !Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Proceed?")
+        .default(false)
+        .interact()
+        .unwrap_or(false) {
+
+        println!("Nothing was removed.");
+        return;
+    }
+
+    if remove_binary {
+        match std::fs::remove_file(&binary) {
+            Ok(()) => println!("✅ Removed {:?}", binary),
+            Err(e) => println!("❌ Failed to remove {:?}: {}", binary, e),
+        }
+    }
+    if remove_assets {
+        match std::fs::remove_dir_all(&assets_dir) {
+            Ok(()) => println!("✅ Removed {:?}", assets_dir),
+            Err(e) => println!("❌ Failed to remove {:?}: {}", assets_dir, e),
+        }
+    }
+    for profile in &profiles {
+        match remove_installer_lines(profile) {
+            Ok(()) => println!("✅ Removed the PATH line from {:?}", profile),
+            Err(e) => println!("❌ Failed to update {:?}: {}", profile, e),
+        }
+    }
+    if assets && !remove_assets {
+        println!("No assets directory at {:?}, so there was nothing to remove there.", assets_dir);
+    }
+}
+
+/// Drop each installer marker from a profile along with the PATH line after it
+/// and the blank line install put before it
+fn remove_installer_lines(profile: &Path) -> io::Result<()> {
+    let text = std::fs::read_to_string(profile)?;
+    let mut kept: Vec<&str> = Vec::new();
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        if line.trim() != INSTALLER_MARKER {
+            kept.push(line);
+            continue;
+        }
+        lines.next();
+        if kept.last().is_some_and(|prev| prev.trim().is_empty()) {
+            kept.pop();
+        }
+    }
+
+    let mut cleaned = kept.join("\n");
+    if !cleaned.is_empty() {
+        cleaned.push('\n');
+    }
+    std::fs::write(profile, cleaned)
+}
+
 /// Show a random productivity tip
-fn show_random_tip(emojis: &Emojis) {
-    let tips = vec![
-        "The Pomodoro Technique works best when you fully commit to the task during work periods.",
-        "Keep a list of small tasks to tackle during short breaks to maintain productivity momentum.",
-        "Physical activity during breaks (like stretching) can boost your energy for the next Pomodoro.",
-        "Try different Pomodoro lengths to find what works best for you - not everyone is optimal at 25 minutes.",
-        "Use Pomodoros to estimate task completion times by tracking how many you need for similar tasks.",
-        "The 'rule of three' suggests focusing on completing just three main tasks per day.",
-        "Consider using noise-cancelling headphones or white noise during Pomodoros to improve focus.",
-        "Hydration improves cognitive function - keep water nearby during your work sessions.",
-        "For creative tasks, sometimes a longer Pomodoro (40-60 minutes) works better than the standard 25.",
-        "Track your completed Pomodoros to visualize your productivity trends over time.",
-        "The Rust crab says: sometimes your most productive Pomodoro isn't the one where you write the most code!",
-    ];
+fn show_random_tip(emojis: &Emojis, settings: &Settings) {
+    // Offline or a slow API falls back to the built-in tips
+    let tip = settings.online_tip.as_ref()
+        .and_then(quote::fetch)
+        .unwrap_or_else(|| random_from(settings.lang.list(|l| l.tips)).to_string());
 
     println!("\n{} {} {}",
              random_from(&emojis.work),
@@ -659,5 +3548,198 @@ fn show_random_tip(emojis: &Emojis) {
 
     println!("{} {}\n",
              "💡",
-             random_from(&tips).bright_green());
+             tip.bright_green());
+}
+
+/// Display a random motivational message from one category
+fn show_motivation(category: MotivationCategory, emojis: &Emojis, motivations: &Motivations) {
+    let messages = match category {
+        MotivationCategory::Start => &motivations.start_work,
+        MotivationCategory::During => &motivations.during_work,
+        MotivationCategory::End => &motivations.end_work,
+    };
+
+    println!("\n{} {} {}\n",
+             random_from(&emojis.work),
+             random_from(messages).bright_green(),
+             random_from(&emojis.rust));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, LocalResult, NaiveDate, NaiveDateTime};
+
+    /// A zone an hour ahead of UTC that springs forward to two hours ahead
+    /// at 01:00 UTC on 2026-03-29, like Central Europe
+    #[derive(Clone, Copy, Debug)]
+    struct SpringForward;
+
+    impl SpringForward {
+        fn switch() -> NaiveDateTime {
+            NaiveDate::from_ymd_opt(2026, 3, 29).unwrap().and_hms_opt(1, 0, 0).unwrap()
+        }
+
+        fn hours(hours: i32) -> FixedOffset {
+            FixedOffset::east_opt(hours * 3600).unwrap()
+        }
+
+        /// `hh:mm:ss` UTC on the day of the change
+        fn utc(h: u32, m: u32, s: u32) -> DateTime<SpringForward> {
+            SpringForward.from_utc_datetime(&Self::switch().date().and_hms_opt(h, m, s).unwrap())
+        }
+    }
+
+    impl TimeZone for SpringForward {
+        type Offset = FixedOffset;
+
+        fn from_offset(_: &FixedOffset) -> SpringForward {
+            SpringForward
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            if *local - chrono::Duration::hours(1) < Self::switch() {
+                LocalResult::Single(Self::hours(1))
+            } else if *local - chrono::Duration::hours(2) >= Self::switch() {
+                LocalResult::Single(Self::hours(2))
+            } else {
+                // The skipped hour
+                LocalResult::None
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            Self::hours(if *utc < Self::switch() { 1 } else { 2 })
+        }
+    }
+
+    /// A clock stopped at one instant
+    struct FixedClock(DateTime<SpringForward>);
+
+    impl Clock for FixedClock {
+        type Tz = SpringForward;
+
+        fn now(&self) -> DateTime<SpringForward> {
+            self.0
+        }
+    }
+
+    #[test]
+    fn eta_before_the_change_has_no_offset() {
+        // 01:30 local, ending at 01:55 local
+        let clock = FixedClock(SpringForward::utc(0, 30, 0));
+        let end = clock.now() + chrono::Duration::minutes(25);
+
+        assert_eq!(format_eta(end, &clock, true), "01:55");
+        assert_eq!(format_eta(end, &clock, false), "01:55:00");
+    }
+
+    #[test]
+    fn eta_across_the_change_shows_the_new_offset() {
+        // 01:50 local, so 25 minutes later the clocks have gone forward: 03:15, not 02:15
+        let clock = FixedClock(SpringForward::utc(0, 50, 0));
+        let end = clock.now() + chrono::Duration::minutes(25);
+
+        assert_eq!(format_eta(end, &clock, true), "03:15 +02:00");
+        assert_eq!(format_eta(end, &clock, false), "03:15:00 +02:00");
+    }
+
+    #[test]
+    fn eta_rounding_crosses_into_the_next_minute() {
+        let clock = FixedClock(SpringForward::utc(0, 10, 0));
+        let end = clock.now() + chrono::Duration::seconds(25 * 60 + 40);
+
+        assert_eq!(format_eta(end, &clock, true), "01:36");
+        assert_eq!(format_eta(end, &clock, false), "01:35:40");
+    }
+
+    #[test]
+    fn eta_after_the_change_has_no_offset() {
+        let clock = FixedClock(SpringForward::utc(2, 0, 0));
+        let end = clock.now() + chrono::Duration::minutes(25);
+
+        assert_eq!(format_eta(end, &clock, true), "04:25");
+    }
+
+    /// A 25-minute countdown started at 00:30 UTC, and the time `seconds` later
+    fn countdown() -> (Countdown<SpringForward>, impl Fn(i64) -> DateTime<SpringForward>) {
+        let start = SpringForward::utc(0, 30, 0);
+        (Countdown::new(25 * 60, start), move |seconds| start + chrono::Duration::seconds(seconds))
+    }
+
+    #[test]
+    fn countdown_carries_part_seconds() {
+        let (mut countdown, at) = countdown();
+        countdown.tick(at(0) + chrono::Duration::milliseconds(600), false);
+        assert_eq!(countdown.remaining, 25 * 60);
+        countdown.tick(at(1) + chrono::Duration::milliseconds(200), false);
+        assert_eq!(countdown.remaining, 25 * 60 - 1);
+    }
+
+    #[test]
+    fn countdown_counts_real_time_across_the_change() {
+        // 01:59:59 to 03:00:00 on the wall is one second
+        let start = SpringForward::utc(0, 59, 59);
+        let mut countdown = Countdown::new(60, start);
+        countdown.tick(SpringForward::utc(1, 0, 0), false);
+        assert_eq!(countdown.remaining, 59);
+    }
+
+    #[test]
+    fn countdown_catches_up_after_a_jump_forward() {
+        let (mut countdown, at) = countdown();
+        countdown.tick(at(10), false);
+        // Asleep for ten minutes
+        countdown.tick(at(10 + 600), false);
+        assert_eq!(countdown.remaining, 25 * 60 - 610);
+
+        // Asleep past the end: done, logging the planned length rather than the time away
+        countdown.tick(at(3 * 3600), false);
+        assert_eq!(countdown.remaining, 0);
+        assert_eq!(history::Rounding::Down.minutes(countdown.total), 25);
+    }
+
+    #[test]
+    fn countdown_ignores_a_jump_backward() {
+        let (mut countdown, at) = countdown();
+        countdown.tick(at(10), false);
+        countdown.tick(at(10 - 3600), false);
+        assert_eq!(countdown.remaining, 25 * 60 - 10);
+
+        // Counting goes on from the new time
+        countdown.tick(at(10 - 3600 + 5), false);
+        assert_eq!(countdown.remaining, 25 * 60 - 15);
+        assert_eq!(history::Rounding::Down.minutes(countdown.total), 25);
+    }
+
+    #[test]
+    fn countdown_holds_while_paused() {
+        let (mut countdown, at) = countdown();
+        countdown.tick(at(10), false);
+        countdown.tick(at(600), true);
+        assert_eq!(countdown.remaining, 25 * 60 - 10);
+        countdown.tick(at(601), false);
+        assert_eq!(countdown.remaining, 25 * 60 - 11);
+    }
+
+    #[test]
+    fn countdown_adjustments_change_what_is_logged() {
+        let (mut countdown, at) = countdown();
+        countdown.add(ADJUST_SECONDS);
+        assert_eq!(history::Rounding::Down.minutes(countdown.total), 30);
+
+        // Cutting never takes the running second
+        countdown.tick(at(30 * 60 - 2), false);
+        countdown.cut(ADJUST_SECONDS);
+        assert_eq!(countdown.remaining, 1);
+        assert_eq!(countdown.total, 30 * 60 - 1);
+    }
 }
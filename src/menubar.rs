@@ -0,0 +1,57 @@
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+use crate::status::{self, RunningTimer};
+
+/// Separates one update from the next in a SwiftBar streamable plugin
+const STREAM_SEPARATOR: &str = "~~~";
+
+/// Print the running timer in the plugin format SwiftBar and xbar read (Argos on
+/// GNOME reads it too): the menu bar title, then `---` and the dropdown lines.
+/// With `stream`, print an update every second for a streamable SwiftBar plugin.
+pub fn print(stream: bool) {
+    if !stream {
+        print!("{}", render(status::running().as_ref()));
+        return;
+    }
+
+    let mut stdout = io::stdout();
+    loop {
+        let update = format!("{}\n{}", STREAM_SEPARATOR, render(status::running().as_ref()));
+        // SwiftBar closing the pipe ends the plugin
+        if stdout.write_all(update.as_bytes()).and_then(|_| stdout.flush()).is_err() {
+            return;
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
+}
+
+fn render(timer: Option<&RunningTimer>) -> String {
+    let Some(timer) = timer else {
+        return "🍅\n---\nNo timer running\n".to_string();
+    };
+
+    let icon = if timer.paused {
+        "⏸"
+    } else if timer.timer_type.contains("Break") {
+        "☕"
+    } else {
+        "🍅"
+    };
+    let mut out = format!("{} {:02}:{:02}\n---\n", icon, timer.remaining / 60, timer.remaining % 60);
+
+    out.push_str(&timer.timer_type);
+    if !timer.task.is_empty() {
+        // A pipe would start the line's SwiftBar parameters
+        out.push_str(&format!(": {}", timer.task.replace('|', "¦")));
+    }
+    out.push('\n');
+
+    let elapsed = timer.total.saturating_sub(timer.remaining);
+    out.push_str(&format!("{} of {} min done", elapsed / 60, timer.total / 60));
+    if timer.paused {
+        out.push_str(" (paused)");
+    }
+    out.push('\n');
+    out
+}
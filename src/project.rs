@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use crate::json;
+
+/// How long to wait for someone else's lock before giving up
+const LOCK_WAIT: Duration = Duration::from_secs(5);
+
+/// A lock older than this was left behind by a crash and is broken
+const STALE_LOCK: Duration = Duration::from_secs(30);
+
+/// The project named by the first `@project` word in a task, e.g. `website` in "Fix nav @website"
+pub fn project_of(task_desc: &str) -> Option<&str> {
+    task_desc.split_whitespace()
+        .find_map(|word| word.strip_prefix('@'))
+        .filter(|project| !project.is_empty())
+}
+
+/// Add one pomodoro to `project` in the shared counts file, creating it if needed,
+/// and return the project's new total. The file is a flat JSON object of counts.
+pub fn record(path: &Path, project: &str) -> Result<u64, String> {
+    let _lock = Lock::acquire(path)?;
+
+    let mut counts: BTreeMap<String, u64> = match fs::read_to_string(path) {
+        Ok(contents) if !contents.trim().is_empty() => json::parse_object(&contents)
+            .ok_or_else(|| format!("{:?} isn't a JSON object of counts", path))?
+            .into_iter()
+            .filter_map(|(name, count)| Some((name, count.parse().ok()?)))
+            .collect(),
+        _ => BTreeMap::new(),
+    };
+
+    let total = counts.entry(project.to_string()).or_default();
+    *total += 1;
+    let total = *total;
+
+    let fields: Vec<String> = counts.iter()
+        .map(|(name, count)| format!("  {}: {}", json::string(name), count))
+        .collect();
+    let contents = format!("{{\n{}\n}}\n", fields.join(",\n"));
+
+    // Write then rename, so readers never see half a file
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, contents).map_err(|e| e.to_string())?;
+    fs::rename(&temp, path).map_err(|e| e.to_string())?;
+
+    Ok(total)
+}
+
+/// A `<file>.lock` next to the counts file, held while it's rewritten, so
+/// teammates on a shared mount don't overwrite each other. Removed on drop.
+struct Lock {
+    path: PathBuf,
+}
+
+impl Lock {
+    fn acquire(file: &Path) -> Result<Lock, String> {
+        let mut name = file.as_os_str().to_owned();
+        name.push(".lock");
+        let path = PathBuf::from(name);
+        let started = SystemTime::now();
+
+        loop {
+            // Creating the file fails if it exists, even across machines on most shared mounts
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(Lock { path }),
+                Err(e) if e.kind() != std::io::ErrorKind::AlreadyExists => return Err(e.to_string()),
+                Err(_) => (),
+            }
+
+            let stale = fs::metadata(&path)
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age > STALE_LOCK);
+            if stale {
+                let _ = fs::remove_file(&path);
+                continue;
+            }
+
+            if started.elapsed().unwrap_or_default() > LOCK_WAIT {
+                return Err(format!("{:?} is locked by someone else", file));
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
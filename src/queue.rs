@@ -0,0 +1,63 @@
+use std::fs;
+use std::path::PathBuf;
+use crate::history;
+
+/// Tasks waiting to be worked on, one per line, first in line at the top, under the data dir
+const QUEUE_FILE: &str = "queue.txt";
+
+fn path() -> Result<PathBuf, String> {
+    history::data_dir()
+        .map(|dir| dir.join(QUEUE_FILE))
+        .ok_or_else(|| "could not determine the data directory".to_string())
+}
+
+/// The queued tasks in order; a missing file is an empty queue
+pub fn list() -> Vec<String> {
+    let contents = path().ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .unwrap_or_default();
+
+    contents.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Put `task` at the end of the queue, returning its position (from 1) and the task as queued
+pub fn add(task: &str) -> Result<(usize, String), String> {
+    // One line per task, so line breaks in the task would split it
+    let task = task.split_whitespace().collect::<Vec<_>>().join(" ");
+    if task.is_empty() {
+        return Err("the task is empty".to_string());
+    }
+
+    let mut tasks = list();
+    tasks.push(task.clone());
+    save(&tasks)?;
+    Ok((tasks.len(), task))
+}
+
+/// Take `task` off the queue once it's been worked on. Read afresh, so tasks
+/// added from another terminal in the meantime are kept.
+pub fn remove(task: &str) -> Result<(), String> {
+    let mut tasks = list();
+    if let Some(index) = tasks.iter().position(|queued| queued == task) {
+        tasks.remove(index);
+        save(&tasks)?;
+    }
+    Ok(())
+}
+
+fn save(tasks: &[String]) -> Result<(), String> {
+    let path = path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+
+    let contents: String = tasks.iter().map(|task| format!("{}\n", task)).collect();
+    // Write then rename, so a crash never leaves half a queue
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, contents).map_err(|e| e.to_string())?;
+    fs::rename(&temp, &path).map_err(|e| e.to_string())
+}
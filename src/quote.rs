@@ -0,0 +1,32 @@
+use std::process::Command;
+use crate::json;
+
+/// Quotes API used by `--online-tip` when no `online_tip_url` is set
+pub const DEFAULT_URL: &str = "https://zenquotes.io/api/random";
+
+/// Where the default API keeps the quote: the `q` field of the first item
+pub const DEFAULT_FIELD: &str = "0.q";
+
+/// Seconds to wait for the quote before falling back to a built-in tip
+const TIMEOUT_SECONDS: &str = "5";
+
+/// An endpoint returning JSON, and the dotted path to the quote inside it
+pub struct OnlineTip {
+    pub url: String,
+    pub field: String,
+}
+
+/// Fetch a quote with `curl`, or `None` if offline, slow, or the reply has no such field
+pub fn fetch(source: &OnlineTip) -> Option<String> {
+    let output = Command::new("curl")
+        .args(["-sS", "--fail", "--max-time", TIMEOUT_SECONDS, "-H", "Accept: application/json", &source.url])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let quote = json::lookup(&String::from_utf8_lossy(&output.stdout), &source.field)?;
+    let quote = quote.trim();
+    (!quote.is_empty()).then(|| quote.to_string())
+}
@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+use std::fs::{self, create_dir_all};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use chrono::{Local, NaiveDate};
+use colored::*;
+use crate::config::Config;
+use crate::history::{self, COMPLETED_DIR};
+use crate::i18n::UnitLabel;
+use crate::stats::{self, Tally};
+
+/// Completed sessions over a run of days, in total and per task
+#[derive(Default)]
+struct Summary {
+    sessions: usize,
+    minutes: u64,
+    /// Sessions and minutes for each task, by name
+    tasks: BTreeMap<String, (usize, u64)>,
+}
+
+impl Summary {
+    /// Add the sessions completed on `date`
+    fn add_day(&mut self, date: NaiveDate) {
+        let entries = history::read_day(COMPLETED_DIR, date);
+
+        for entry in &entries {
+            let task = self.tasks.entry(entry.task.clone()).or_default();
+            task.0 += 1;
+            task.1 += entry.minutes.unwrap_or(0);
+        }
+
+        self.sessions += entries.len();
+        self.minutes += entries.iter().filter_map(|entry| entry.minutes).sum::<u64>();
+    }
+}
+
+/// Render a Markdown summary of the week starting on `week_start`, with the focus score if `with_score`
+pub fn weekly_markdown(week_start: NaiveDate, with_score: bool) -> String {
+    let week_end = week_start + chrono::Duration::days(6);
+    let mut out = format!("# Pomodoro report: {} to {}\n\n", week_start, week_end);
+
+    out.push_str("| Day | Sessions | Minutes |\n");
+    out.push_str("| --- | ---: | ---: |\n");
+
+    let mut summary = Summary::default();
+    for date in week_start.iter_days().take(7) {
+        let (sessions, minutes) = (summary.sessions, summary.minutes);
+        summary.add_day(date);
+        out.push_str(&format!("| {} | {} | {} |\n", date.format("%a %Y-%m-%d"),
+                              summary.sessions - sessions, summary.minutes - minutes));
+    }
+    let Summary { sessions: total_sessions, minutes: total_minutes, tasks } = summary;
+
+    out.push_str(&format!("\n**Total:** {} sessions, {} minutes\n", total_sessions, total_minutes));
+
+    if with_score {
+        let score = Tally::between(week_start, week_end).percent()
+            .map(|percent| format!("{:.0}%", percent))
+            .unwrap_or_else(|| "no data".to_string());
+        out.push_str(&format!("**Focus score:** {}\n", score));
+    }
+
+    if !tasks.is_empty() {
+        out.push_str("\n## Tasks\n\n");
+        for (task, (sessions, minutes)) in &tasks {
+            out.push_str(&format!("- {}: {} sessions, {} min\n", task, sessions, minutes));
+        }
+    }
+
+    out
+}
+
+/// Print a boxed recap of today's sessions: how many, how long, on which tasks,
+/// and how that compares with `goal`, if there is one
+pub fn print_day_summary(goal: Option<u64>, unit: &UnitLabel) {
+    let today = Local::now().date_naive();
+    let mut summary = Summary::default();
+    summary.add_day(today);
+
+    let sessions = summary.sessions as u64;
+    let mut lines = vec![
+        format!("Today, {}", today.format("%a %Y-%m-%d")),
+        format!("{} {}, {}h {:02}m", sessions, unit.count(sessions), summary.minutes / 60, summary.minutes % 60),
+    ];
+    if let Some(goal) = goal {
+        lines.push(match goal.checked_sub(sessions) {
+            Some(0) | None => format!("Goal of {} reached", goal),
+            Some(left) => format!("Goal: {} of {}, {} to go", sessions, goal, left),
+        });
+    }
+    if !summary.tasks.is_empty() {
+        lines.push(String::new());
+        let width = summary.tasks.keys().map(|task| task.chars().count()).max().unwrap_or(0);
+        for (task, (count, minutes)) in &summary.tasks {
+            let padding = " ".repeat(width - task.chars().count());
+            lines.push(format!("{}{}  {} min, {} {}", task, padding, minutes, count, unit.count(*count as u64)));
+        }
+    }
+
+    let inner = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+    println!("\n╭{}╮", "─".repeat(inner + 2));
+    for (index, line) in lines.iter().enumerate() {
+        let padded = format!("{}{}", line, " ".repeat(inner - line.chars().count()));
+        let styled = if index == 0 { padded.bright_yellow().bold() } else { padded.normal() };
+        println!("│ {} │", styled);
+    }
+    println!("╰{}╯\n", "─".repeat(inner + 2));
+}
+
+/// Write this week's report under the data dir, print it, and optionally email it
+pub fn run_report(email: bool, config: &Config, with_score: bool, start: stats::WeekStart) {
+    let week_start = stats::week_start(Local::now().date_naive(), start);
+    let report = weekly_markdown(week_start, with_score);
+
+    println!("{}", report);
+
+    match save_report(week_start, &report) {
+        Ok(path) => println!("{} {:?}", "Report saved to".bright_green(), path),
+        Err(e) => eprintln!("❌ Failed to save report: {}", e),
+    }
+
+    if email {
+        match send_report(week_start, &report, config) {
+            Ok(to) => println!("📧 Report sent to {}", to.bright_cyan()),
+            Err(e) => eprintln!("❌ Failed to email report: {}", e),
+        }
+    }
+}
+
+/// Save the report as `reports/week-YYYY-MM-DD.md` in the data dir
+fn save_report(week_start: NaiveDate, report: &str) -> Result<PathBuf, String> {
+    let dir = history::data_dir()
+        .ok_or("could not determine the data directory")?
+        .join("reports");
+    create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let path = dir.join(format!("week-{}.md", week_start));
+    fs::write(&path, report).map_err(|e| e.to_string())?;
+
+    Ok(path)
+}
+
+/// Hand the report to the local `sendmail` (or the configured command)
+fn send_report(week_start: NaiveDate, report: &str, config: &Config) -> Result<String, String> {
+    let to = config.email_to.clone()
+        .ok_or("set `email_to` in your config file to email reports")?;
+    let sendmail = config.sendmail_command.clone().unwrap_or_else(|| "sendmail".to_string());
+
+    let mut child = Command::new(&sendmail)
+        .arg("-t")
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not run {:?}: {} (set `sendmail_command` in your config)", sendmail, e))?;
+
+    let message = format!(
+        "To: {}\nSubject: Pomodoro report for the week of {}\nContent-Type: text/plain; charset=utf-8\n\n{}",
+        to, week_start, report);
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(message.as_bytes()).map_err(|e| e.to_string())?;
+    }
+
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err(format!("{:?} exited with {}", sendmail, status));
+    }
+
+    Ok(to)
+}
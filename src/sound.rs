@@ -0,0 +1,381 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use clap::ValueEnum;
+use rand::seq::SliceRandom;
+use crate::status;
+
+/// What to do when an alert is requested while another is still playing
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Overlap {
+    /// Play it after the current one finishes
+    Queue,
+    /// Skip it
+    Drop,
+}
+
+impl Overlap {
+    /// Parse a config value
+    pub fn from_name(name: &str) -> Option<Overlap> {
+        Overlap::from_str(name, true).ok()
+    }
+}
+
+/// Named sets of sounds, picked with `--sound-theme`. A theme's files sit in a
+/// folder of its name wherever sounds are looked up (see `find_sound`), e.g.
+/// `assets/bell/complete.wav`. Missing files fall back to the default alert,
+/// or to silence for the start sound.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum SoundTheme {
+    /// The default alert only
+    Chime,
+    Bell,
+    Retro,
+}
+
+impl SoundTheme {
+    /// Parse a config value
+    pub fn from_name(name: &str) -> Option<SoundTheme> {
+        SoundTheme::from_str(name, true).ok()
+    }
+
+    /// Sound played as a work session starts
+    fn start(self) -> Option<&'static str> {
+        match self {
+            SoundTheme::Chime => None,
+            SoundTheme::Bell => Some("bell/start.wav"),
+            SoundTheme::Retro => Some("retro/start.wav"),
+        }
+    }
+
+    /// Sound played when a timer completes
+    pub fn complete(self) -> &'static str {
+        match self {
+            SoundTheme::Chime => "alert.wav",
+            SoundTheme::Bell => "bell/complete.wav",
+            SoundTheme::Retro => "retro/complete.wav",
+        }
+    }
+}
+
+/// Default alert, bundled so there's always something to play
+const DEFAULT_ALERT: &[u8] = include_bytes!("assets/alert.wav");
+
+/// How and where alert sounds are played
+#[derive(Clone)]
+pub struct SoundSettings {
+    /// False when sounds are turned off with `--no-sound`
+    pub enabled: bool,
+    /// Custom alert file given with `--sound`
+    pub custom: Option<PathBuf>,
+    /// Directory of alerts to pick one from at random each time, given with `--sound-dir`
+    pub sound_dir: Option<PathBuf>,
+    pub overlap: Overlap,
+    pub theme: SoundTheme,
+    /// Times the completion alert plays in a row, from 1 to `MAX_ALERT_REPEAT`
+    pub repeat: u32,
+    /// Directory searched for sound files before the default locations
+    pub assets_dir: Option<PathBuf>,
+}
+
+/// Most times `--alert-repeat` will play an alert in a row
+pub const MAX_ALERT_REPEAT: u32 = 10;
+
+/// Pause between repeats of an alert
+const REPEAT_GAP: Duration = Duration::from_millis(400);
+
+/// Held while an alert is playing, so only one plays at a time
+static PLAYING: Mutex<()> = Mutex::new(());
+
+/// Alerts still playing in the background, waited on before exit
+static PENDING: Mutex<Vec<JoinHandle<()>>> = Mutex::new(Vec::new());
+
+/// Look for a sound file in the assets dir, if set, then the usual places
+pub fn find_sound(name: &str, settings: &SoundSettings) -> Option<PathBuf> {
+    let mut candidates = Vec::new();
+
+    if let Some(dir) = &settings.assets_dir {
+        candidates.push(dir.join(name));
+    }
+
+    candidates.push(PathBuf::from("src/assets").join(name));
+    candidates.push(PathBuf::from("assets").join(name));
+
+    // Installed binaries keep their assets next to them
+    if let Some(exe_dir) = std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf)) {
+        candidates.push(exe_dir.join("assets").join(name));
+    }
+
+    candidates.into_iter().find(|path| path.is_file())
+}
+
+/// The alert to play: `--sound`, then a random one from `--sound-dir`, then the theme's,
+/// then an `alert.wav` on disk, then the bundled one
+pub fn resolve_alert(settings: &SoundSettings) -> Option<PathBuf> {
+    settings.custom.clone().filter(|path| path.is_file())
+        .or_else(|| settings.sound_dir.as_deref().and_then(random_sound))
+        .or_else(|| find_sound(settings.theme.complete(), settings))
+        .or_else(|| find_sound("alert.wav", settings))
+        .or_else(embedded_alert)
+}
+
+/// A `.wav` from `dir` picked at random, or `None` if it has none or can't be read.
+/// Other formats are skipped, like everywhere else sounds are looked up.
+fn random_sound(dir: &Path) -> Option<PathBuf> {
+    let sounds: Vec<PathBuf> = std::fs::read_dir(dir).ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| path.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("wav")))
+        .collect();
+
+    sounds.choose(&mut rand::thread_rng()).cloned()
+}
+
+/// Write the bundled alert to a temp file so command-line players can open it
+fn embedded_alert() -> Option<PathBuf> {
+    let path = std::env::temp_dir().join("pomodoro_rs-alert.wav");
+
+    let up_to_date = std::fs::metadata(&path)
+        .map(|meta| meta.len() == DEFAULT_ALERT.len() as u64)
+        .unwrap_or(false);
+    if !up_to_date {
+        std::fs::write(&path, DEFAULT_ALERT).ok()?;
+    }
+
+    Some(path)
+}
+
+/// Play the alert sound in the background
+pub fn play_alert_sound(settings: &SoundSettings) {
+    if !settings.enabled || status::presenting() {
+        return;
+    }
+
+    if let Some(path) = resolve_alert(settings) {
+        play_in_background(path, settings.overlap, settings.repeat);
+    }
+}
+
+/// Play `schedule_complete.wav` in the background as a whole schedule finishes,
+/// or the usual alert if there isn't one
+pub fn play_schedule_complete_sound(settings: &SoundSettings) {
+    if !settings.enabled || status::presenting() {
+        return;
+    }
+
+    if let Some(path) = find_sound("schedule_complete.wav", settings).or_else(|| resolve_alert(settings)) {
+        play_in_background(path, settings.overlap, settings.repeat);
+    }
+}
+
+/// Play the theme's start sound, if it has one, in the background
+pub fn play_start_sound(settings: &SoundSettings) {
+    if !settings.enabled || status::presenting() {
+        return;
+    }
+
+    if let Some(path) = settings.theme.start().and_then(|name| find_sound(name, settings)) {
+        play_in_background(path, settings.overlap, 1);
+    }
+}
+
+/// Play `milestone.wav`, if there is one, in the background. It's only a nudge,
+/// so it's skipped rather than queued when something else is playing.
+pub fn play_milestone_sound(settings: &SoundSettings) {
+    if !settings.enabled || status::presenting() {
+        return;
+    }
+
+    if let Some(path) = find_sound("milestone.wav", settings) {
+        play_in_background(path, Overlap::Drop, 1);
+    }
+}
+
+/// Say `text` aloud with the platform's text-to-speech tool on its own thread, so the
+/// timer doesn't wait on it. Nothing happens when no tool is installed.
+pub fn speak(text: &str, settings: &SoundSettings) {
+    if !settings.enabled || status::presenting() {
+        return;
+    }
+
+    let text = text.to_string();
+    thread::spawn(move || {
+        for (program, args) in speakers(&text) {
+            let spoken = Command::new(program)
+                .args(&args)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+            // Only a tool that isn't installed is worth trying the next one for
+            if spoken.is_ok() {
+                break;
+            }
+        }
+    });
+}
+
+/// Candidate text-to-speech tools and their arguments for this platform
+fn speakers(text: &str) -> Vec<(&'static str, Vec<String>)> {
+    if cfg!(target_os = "macos") {
+        vec![("say", vec![text.to_string()])]
+    } else if cfg!(windows) {
+        let script = format!("Add-Type -AssemblyName System.Speech; (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak('{}')",
+                             text.replace('\'', "''"));
+        vec![("powershell", vec!["-NoProfile".to_string(), "-Command".to_string(), script])]
+    } else {
+        vec![
+            ("spd-say", vec!["--wait".to_string(), text.to_string()]),
+            ("espeak-ng", vec![text.to_string()]),
+            ("espeak", vec![text.to_string()]),
+        ]
+    }
+}
+
+/// Play `path` `times` times in a row on its own thread, holding the lock throughout
+/// so other alerts don't cut in between repeats
+fn play_in_background(path: PathBuf, overlap: Overlap, times: u32) {
+    let handle = thread::spawn(move || {
+        let _playing = match overlap {
+            Overlap::Queue => PLAYING.lock().unwrap_or_else(|e| e.into_inner()),
+            Overlap::Drop => match PLAYING.try_lock() {
+                Ok(guard) => guard,
+                Err(_) => return,
+            },
+        };
+
+        for round in 0..times {
+            if round > 0 {
+                thread::sleep(REPEAT_GAP);
+            }
+            // No player means no point trying again
+            if play_file(&path).is_err() {
+                break;
+            }
+        }
+    });
+
+    if let Ok(mut pending) = PENDING.lock() {
+        pending.retain(|handle| !handle.is_finished());
+        pending.push(handle);
+    }
+}
+
+/// Wait for background alerts to finish so they aren't cut off on exit
+pub fn finish() {
+    let handles: Vec<_> = match PENDING.lock() {
+        Ok(mut pending) => pending.drain(..).collect(),
+        Err(_) => return,
+    };
+
+    for handle in handles {
+        let _ = handle.join();
+    }
+}
+
+/// Play a sound file with the platform's command-line player, blocking until it ends
+pub fn play_file(path: &Path) -> Result<(), String> {
+    let mut tried = Vec::new();
+
+    for (program, args) in players(path) {
+        match Command::new(program)
+            .args(&args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status() {
+            Ok(status) if status.success() => return Ok(()),
+            Ok(status) => return Err(format!("{} exited with {}", program, status)),
+            // Not installed, try the next one
+            Err(_) => tried.push(program),
+        }
+    }
+
+    Err(format!("no audio player found (tried {})", tried.join(", ")))
+}
+
+/// Candidate players and their arguments for this platform
+fn players(path: &Path) -> Vec<(&'static str, Vec<String>)> {
+    let file = path.to_string_lossy().to_string();
+
+    if cfg!(target_os = "macos") {
+        vec![("afplay", vec![file])]
+    } else if cfg!(windows) {
+        let script = format!("(New-Object Media.SoundPlayer '{}').PlaySync()", file.replace('\'', "''"));
+        vec![("powershell", vec!["-NoProfile".to_string(), "-Command".to_string(), script])]
+    } else {
+        vec![
+            ("paplay", vec![file.clone()]),
+            ("pw-play", vec![file.clone()]),
+            ("aplay", vec!["-q".to_string(), file]),
+        ]
+    }
+}
+
+/// What a WAV file holds, read from its header
+pub struct WavInfo {
+    /// `PCM`, `IEEE float` or the raw format code
+    pub encoding: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub bits_per_sample: u16,
+    pub duration_seconds: f64,
+}
+
+/// Read the format and length of a WAV file without decoding the samples.
+/// Other formats are rejected, since the command-line players are only given WAVs.
+pub fn wav_info(path: &Path) -> Result<WavInfo, String> {
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("not a WAV file (no RIFF/WAVE header)".to_string());
+    }
+
+    let u16_at = |at: usize| u16::from_le_bytes([bytes[at], bytes[at + 1]]);
+    let u32_at = |at: usize| u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]);
+
+    let mut format = None;
+    let mut data_len = None;
+    let mut at = 12;
+    while at + 8 <= bytes.len() {
+        let id = &bytes[at..at + 4];
+        let body = at + 8;
+        // Streamed files can leave the size unset, so never read past the end
+        let len = (u32_at(at + 4) as usize).min(bytes.len() - body);
+
+        if id == b"fmt " && len >= 16 {
+            let mut code = u16_at(body);
+            // WAVE_FORMAT_EXTENSIBLE keeps the real format at the start of its sub-format GUID
+            if code == 0xFFFE && len >= 26 {
+                code = u16_at(body + 24);
+            }
+            format = Some((code, u16_at(body + 2), u32_at(body + 4), u32_at(body + 8), u16_at(body + 14)));
+        } else if id == b"data" {
+            data_len = Some(len);
+        }
+
+        // Chunks are padded to an even length
+        at = body + len + len % 2;
+    }
+
+    let (code, channels, sample_rate, byte_rate, bits_per_sample) = format.ok_or("missing `fmt ` chunk")?;
+    let data_len = data_len.ok_or("missing `data` chunk")?;
+    if byte_rate == 0 {
+        return Err("header gives a byte rate of 0".to_string());
+    }
+
+    let encoding = match code {
+        1 => "PCM".to_string(),
+        3 => "IEEE float".to_string(),
+        other => format!("format {:#06x}", other),
+    };
+
+    Ok(WavInfo {
+        encoding,
+        sample_rate,
+        channels,
+        bits_per_sample,
+        duration_seconds: data_len as f64 / byte_rate as f64,
+    })
+}
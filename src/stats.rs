@@ -0,0 +1,309 @@
+use chrono::{Datelike, Local, NaiveDate};
+use clap::ValueEnum;
+use colored::*;
+use crate::history::{self, COMPLETED_DIR, INTERRUPTED_DIR, SKIPPED_BREAKS_DIR, STARTED_DIR};
+use crate::i18n::UnitLabel;
+
+/// Started and completed session counts over a range of days
+pub struct Tally {
+    pub started: usize,
+    pub completed: usize,
+}
+
+impl Tally {
+    /// Count sessions logged from `from` to `to`, inclusive
+    pub fn between(from: NaiveDate, to: NaiveDate) -> Tally {
+        let mut tally = Tally { started: 0, completed: 0 };
+
+        for date in from.iter_days().take_while(|date| *date <= to) {
+            let started = history::read_day(STARTED_DIR, date).len();
+            let completed = history::read_day(COMPLETED_DIR, date).len();
+
+            // Days logged before start tracking existed only have completions
+            tally.started += started.max(completed);
+            tally.completed += completed;
+        }
+
+        tally
+    }
+
+    /// Completion ratio as a percentage, or `None` when nothing was started
+    pub fn percent(&self) -> Option<f64> {
+        if self.started == 0 {
+            None
+        } else {
+            Some(self.completed as f64 / self.started as f64 * 100.0)
+        }
+    }
+
+    /// Format the completion ratio, or a friendly note when nothing was started
+    fn describe(&self) -> String {
+        let Some(percent) = self.percent() else {
+            return "no data yet".dimmed().to_string();
+        };
+
+        let score = format!("{:.0}%", percent);
+        let score = if percent >= 80.0 {
+            score.bright_green()
+        } else if percent >= 50.0 {
+            score.bright_yellow()
+        } else {
+            score.bright_red()
+        };
+
+        format!("{} ({}/{} sessions completed)", score, self.completed, self.started)
+    }
+}
+
+/// The day weeks begin on in weekly stats and reports
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum WeekStart {
+    /// ISO weeks
+    Monday,
+    Sunday,
+}
+
+impl WeekStart {
+    /// Parse a config value
+    pub fn from_name(name: &str) -> Option<WeekStart> {
+        WeekStart::from_str(name, true).ok()
+    }
+}
+
+/// First day of the week containing `date`
+pub fn week_start(date: NaiveDate, start: WeekStart) -> NaiveDate {
+    let days = match start {
+        WeekStart::Monday => date.weekday().num_days_from_monday(),
+        WeekStart::Sunday => date.weekday().num_days_from_sunday(),
+    };
+    date - chrono::Duration::days(days as i64)
+}
+
+/// Sessions from `from` to `to`, inclusive, with each interrupted one counting for
+/// the share of its planned length that it ran, e.g. 15 of 25 minutes is 0.6
+pub fn credit_between(from: NaiveDate, to: NaiveDate) -> f64 {
+    from.iter_days().take_while(|date| *date <= to)
+        .map(|date| {
+            let completed = history::read_day(COMPLETED_DIR, date).len() as f64;
+            let partial: f64 = history::read_day(INTERRUPTED_DIR, date).iter()
+                .filter_map(|entry| match (entry.minutes, entry.planned) {
+                    (Some(minutes), Some(planned)) if planned > 0 => Some((minutes as f64 / planned as f64).min(1.0)),
+                    _ => None,
+                })
+                .sum();
+            completed + partial
+        })
+        .sum()
+}
+
+/// Show today's and this week's focus score (completed / started sessions).
+/// Without `with_score` only completions are shown, since starts aren't being recorded.
+/// With `fractional`, interrupted sessions' partial credit is added up too.
+pub fn show_focus_score(with_score: bool, fractional: bool, start: WeekStart, unit: &UnitLabel) {
+    let today = Local::now().date_naive();
+    let week_start = week_start(today, start);
+
+    if with_score {
+        println!("\n📊 {}", "Focus score".bright_yellow());
+        println!("  Today:     {}", Tally::between(today, today).describe());
+        println!("  This week: {}", Tally::between(week_start, today).describe());
+    } else {
+        println!("\n📊 {}", "Completed sessions".bright_yellow());
+        println!("  Today:     {}", Tally::between(today, today).completed.to_string().bright_cyan());
+        println!("  This week: {}", Tally::between(week_start, today).completed.to_string().bright_cyan());
+    }
+    println!("  All-time:  {} completed", history::lifetime_count().to_string().bright_cyan());
+
+    if fractional {
+        println!("  Counting partial {}: {} today, {} this week",
+                 unit.plural(),
+                 format!("{:.1}", credit_between(today, today)).bright_cyan(),
+                 format!("{:.1}", credit_between(week_start, today)).bright_cyan());
+    }
+
+    let skipped = history::read_day(SKIPPED_BREAKS_DIR, today).len();
+    if skipped > 0 {
+        println!("  Skipped breaks today: {}", skipped.to_string().bright_magenta());
+    }
+    println!();
+}
+
+/// Show a GitHub-style grid of completed pomodoros per day over the last 52 weeks
+pub fn show_heatmap(unit: &UnitLabel, start: WeekStart) {
+    let today = Local::now().date_naive();
+    // Start on the first day of a week so every column is one whole week
+    let first = week_start(today, start) - chrono::Duration::weeks(52);
+    let weeks = 53;
+
+    let counts: Vec<usize> = first.iter_days()
+        .take_while(|date| *date <= today)
+        .map(|date| history::read_day(COMPLETED_DIR, date).len())
+        .collect();
+
+    println!("\n🗓️  {}", format!("{} over the last year", unit.plural_title()).bright_yellow());
+
+    // Month labels above the first week of each month, skipping any that would overlap
+    let mut header = String::from("     ");
+    let mut last_month = 0;
+    for week in 0..weeks {
+        let date = first + chrono::Duration::weeks(week);
+        let column = 5 + week as usize * 2;
+
+        if date.month() != last_month && header.len() <= column {
+            header.push_str(&" ".repeat(column - header.len()));
+            header.push_str(&date.format("%b").to_string());
+            last_month = date.month();
+        }
+    }
+    println!("{}", header.trim_end().dimmed());
+
+    let names = match start {
+        WeekStart::Monday => ["Mon", "", "Wed", "", "Fri", "", "Sun"],
+        WeekStart::Sunday => ["", "Mon", "", "Wed", "", "Fri", ""],
+    };
+    for (day, name) in names.iter().enumerate() {
+        let mut row = format!("{:<4} ", name);
+
+        for week in 0..weeks as usize {
+            let cell = match counts.get(week * 7 + day) {
+                Some(&count) => heat_cell(count),
+                // Days after today
+                None => "  ".to_string(),
+            };
+            row.push_str(&cell);
+        }
+
+        println!("{}", row);
+    }
+
+    let total: usize = counts.iter().sum();
+    let active = counts.iter().filter(|count| **count > 0).count();
+    println!("\n     Less {}{}{}{}{} More",
+             heat_cell(0), heat_cell(1), heat_cell(3), heat_cell(5), heat_cell(8));
+    println!("     {} {} on {} days\n", total.to_string().bright_green(), unit.count(total as u64), active);
+}
+
+/// One colored block, shaded by the number of pomodoros that day
+fn heat_cell(count: usize) -> String {
+    match count {
+        0 => "■ ".truecolor(60, 60, 60).to_string(),
+        1..=2 => "■ ".truecolor(14, 68, 41).to_string(),
+        3..=4 => "■ ".truecolor(0, 109, 50).to_string(),
+        5..=7 => "■ ".truecolor(38, 166, 65).to_string(),
+        _ => "■ ".truecolor(57, 211, 83).to_string(),
+    }
+}
+
+/// Characters for sparklines, lowest to highest
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Show completed sessions per week over the last `weeks` weeks as a sparkline,
+/// with the change from each week to the next
+pub fn show_trends(weeks: usize, unit: &UnitLabel, start: WeekStart) {
+    let today = Local::now().date_naive();
+    let this_week = week_start(today, start);
+
+    // (week start, sessions, minutes), oldest first
+    let mut totals: Vec<(NaiveDate, usize, u64)> = (0..weeks.max(1) as i64).rev()
+        .map(|ago| {
+            let start = this_week - chrono::Duration::weeks(ago);
+            let entries: Vec<_> = start.iter_days().take(7)
+                .take_while(|date| *date <= today)
+                .flat_map(|date| history::read_day(COMPLETED_DIR, date))
+                .collect();
+            (start, entries.len(), entries.iter().filter_map(|entry| entry.minutes).sum())
+        })
+        .collect();
+
+    // Don't count the weeks before you started
+    let first = totals.iter().position(|(_, sessions, _)| *sessions > 0);
+    let Some(first) = first else {
+        println!("\n📈 {}\n", format!("No sessions logged yet, come back after a few {}!", unit.plural()).dimmed());
+        return;
+    };
+    totals.drain(..first);
+
+    let max = totals.iter().map(|(_, sessions, _)| *sessions).max().unwrap_or(0).max(1);
+    let sparkline: String = totals.iter()
+        .map(|(_, sessions, _)| SPARKS[sessions * (SPARKS.len() - 1) / max])
+        .collect();
+
+    println!("\n📈 {} ({} weeks)", "Focus trends".bright_yellow(), totals.len());
+    println!("  {}  {}\n", sparkline.bright_green(), "sessions per week".dimmed());
+
+    let mut previous: Option<usize> = None;
+    for (start, sessions, minutes) in &totals {
+        let change = previous.map(|previous| week_change(previous, *sessions)).unwrap_or_default();
+        let note = if *start == this_week { " (so far)" } else { "" };
+
+        println!("  Week of {}: {:>3} sessions, {:>4} min{}{}",
+                 start.format("%Y-%m-%d"), sessions, minutes, change, note.dimmed());
+        previous = Some(*sessions);
+    }
+
+    if totals.len() < 2 {
+        println!("\n  {}", "Trends need at least two weeks of history.".dimmed());
+    }
+    println!();
+}
+
+/// Week-over-week change as a colored percentage, e.g. " +20%"
+fn week_change(previous: usize, current: usize) -> String {
+    if previous == 0 {
+        return if current == 0 { String::new() } else { format!("  {}", "new".bright_green()) };
+    }
+
+    let percent = (current as f64 - previous as f64) / previous as f64 * 100.0;
+    let text = format!("{:+.0}%", percent);
+    let text = if percent > 0.0 {
+        text.bright_green()
+    } else if percent < 0.0 {
+        text.bright_red()
+    } else {
+        text.normal()
+    };
+
+    format!("  {}", text)
+}
+
+/// Show the total time and sessions ever logged for tasks matching `query`,
+/// ignoring case. Without `exact`, any task containing `query` matches.
+pub fn show_task_time(query: &str, exact: bool, unit: &UnitLabel) {
+    let needle = query.to_lowercase();
+    let matches = |task: &str| {
+        let task = task.to_lowercase();
+        if exact { task == needle } else { task.contains(&needle) }
+    };
+
+    let entries: Vec<_> = history::read_all(COMPLETED_DIR).into_iter()
+        .filter(|entry| matches(&entry.task))
+        .collect();
+
+    if entries.is_empty() {
+        println!("\n⏱️  {}\n", format!("No sessions logged for {:?} yet.", query).dimmed());
+        return;
+    }
+
+    let minutes: u64 = entries.iter().filter_map(|entry| entry.minutes).sum();
+    let sessions = entries.len() as u64;
+    println!("\n⏱️  {} {} in {} {}",
+             format!("{}h {:02}m", minutes / 60, minutes % 60).bright_green(),
+             "total".bright_yellow(),
+             sessions.to_string().bright_cyan(),
+             unit.count(sessions));
+
+    // A substring can catch several tasks; show which ones went into the total
+    let mut tasks: Vec<&str> = entries.iter().map(|entry| entry.task.as_str()).collect();
+    tasks.sort_unstable_by_key(|task| task.to_lowercase());
+    tasks.dedup_by(|a, b| a.to_lowercase() == b.to_lowercase());
+    if tasks.len() > 1 {
+        println!("   {}", format!("Matched: {}", tasks.join(", ")).dimmed());
+    }
+
+    // Lines written before durations were logged don't add to the total
+    let untimed = entries.iter().filter(|entry| entry.minutes.is_none()).count();
+    if untimed > 0 {
+        println!("   {}", format!("{} sessions have no logged duration.", untimed).yellow());
+    }
+    println!();
+}
@@ -0,0 +1,379 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Local, TimeZone, Utc};
+use clap::ValueEnum;
+use crate::{history, json};
+
+/// File in the data dir describing the running timer, removed when it ends
+const CURRENT_FILE: &str = "current.json";
+
+/// File in the data dir that exists while presentation mode is on
+const PRESENTING_FILE: &str = "presenting";
+
+/// Small progress ring drawn before the time, filling up as the session goes on
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Spinner {
+    /// Braille dots filling clockwise
+    Braille,
+    /// A circle filling up in quarters
+    Dots,
+    None,
+}
+
+/// Braille cells with one more dot each, going clockwise from the top left
+const BRAILLE_RING: &[&str] = &["⠀", "⠁", "⠉", "⠙", "⠹", "⢹", "⣹", "⣽", "⣿"];
+
+const DOTS_RING: &[&str] = &["○", "◔", "◑", "◕", "●"];
+
+impl Spinner {
+    /// Parse a config value
+    pub fn from_name(name: &str) -> Option<Spinner> {
+        Spinner::from_str(name, true).ok()
+    }
+
+    /// The ring for `done` out of `total`, or `None` when turned off
+    pub fn glyph(self, done: u64, total: u64) -> Option<&'static str> {
+        let frames = match self {
+            Spinner::Braille => BRAILLE_RING,
+            Spinner::Dots => DOTS_RING,
+            Spinner::None => return None,
+        };
+
+        let last = frames.len() as u64 - 1;
+        let index = (done * last).checked_div(total).map_or(last, |i| i.min(last));
+        Some(frames[index as usize])
+    }
+}
+
+/// Writes the timer state to a named pipe, e.g. for i3blocks or polybar
+pub struct StatusFifo {
+    path: PathBuf,
+    file: Option<File>,
+}
+
+impl StatusFifo {
+    /// Create the FIFO if it doesn't exist yet; nothing is opened until there's a reader
+    pub fn new(path: &Path) -> StatusFifo {
+        #[cfg(unix)]
+        if !path.exists()
+            && let Ok(c_path) = std::ffi::CString::new(path.to_string_lossy().as_bytes()) {
+            unsafe {
+                libc::mkfifo(c_path.as_ptr(), 0o644);
+            }
+        }
+
+        StatusFifo { path: path.to_path_buf(), file: None }
+    }
+
+    /// Write one status line, silently skipping it when no reader is attached
+    pub fn write(&mut self, line: &str) {
+        if self.file.is_none() {
+            self.file = open_nonblocking(&self.path);
+        }
+
+        if let Some(file) = &mut self.file {
+            // The reader went away; reopen once a new one shows up
+            if writeln!(file, "{}", line).is_err() {
+                self.file = None;
+            }
+        }
+    }
+}
+
+/// Open the FIFO for writing without blocking; fails while nobody is reading
+#[cfg(unix)]
+fn open_nonblocking(path: &Path) -> Option<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    std::fs::OpenOptions::new()
+        .write(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(path)
+        .ok()
+}
+
+/// Named pipes are a Unix feature; elsewhere the status is never written
+#[cfg(not(unix))]
+fn open_nonblocking(_path: &Path) -> Option<File> {
+    None
+}
+
+/// A single desktop notification showing how far a session has got, with a
+/// progress bar where the desktop supports the `value` hint (like GNOME).
+/// It's updated in place rather than popping up anew, and closed when dropped.
+/// Elsewhere (macOS, Windows) notifications can't be replaced, so this does nothing.
+pub struct ProgressNotification {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    handle: Option<notify_rust::NotificationHandle>,
+    /// Minutes elapsed at the last update, or `None` before the first
+    #[cfg(all(unix, not(target_os = "macos")))]
+    shown: Option<u64>,
+}
+
+impl ProgressNotification {
+    pub fn new() -> ProgressNotification {
+        ProgressNotification {
+            #[cfg(all(unix, not(target_os = "macos")))]
+            handle: None,
+            #[cfg(all(unix, not(target_os = "macos")))]
+            shown: None,
+        }
+    }
+
+    /// Show the time left, at most once a minute
+    #[cfg(all(unix, not(target_os = "macos")))]
+    pub fn update(&mut self, title: &str, elapsed: u64, total: u64) {
+        use notify_rust::{Hint, Notification, Timeout};
+
+        let minute = elapsed / 60;
+        let first = self.shown.is_none();
+        if self.shown == Some(minute) {
+            return;
+        }
+        self.shown = Some(minute);
+
+        let percent = (elapsed * 100).checked_div(total).map_or(100, |p| p.min(100)) as i32;
+        let body = format!("{} min left", total.saturating_sub(elapsed).div_ceil(60));
+
+        if let Some(handle) = &mut self.handle {
+            handle.body(&body).hint(Hint::CustomInt("value".to_string(), percent));
+            handle.update();
+        } else if first {
+            // Only tried once, so a missing notification server isn't retried every minute
+            self.handle = Notification::new()
+                .summary(title)
+                .body(&body)
+                .hint(Hint::CustomInt("value".to_string(), percent))
+                .hint(Hint::Transient(true))
+                .timeout(Timeout::Never)
+                .show()
+                .ok();
+        }
+    }
+
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    pub fn update(&mut self, _title: &str, _elapsed: u64, _total: u64) {}
+}
+
+impl Drop for ProgressNotification {
+    fn drop(&mut self) {
+        #[cfg(all(unix, not(target_os = "macos")))]
+        if let Some(handle) = self.handle.take() {
+            handle.close();
+        }
+    }
+}
+
+/// Keeps `current.json` up to date while a timer runs, so other commands
+/// (like `remaining`) can see it. The file is removed when this is dropped,
+/// so one left behind belongs to a timer cut short by a crash or reboot.
+pub struct CurrentTimer {
+    path: Option<PathBuf>,
+    timer_type: String,
+    task: String,
+    /// When the timer started, as a Unix timestamp
+    started: i64,
+    /// Last written end time and pause state, to skip rewriting an unchanged file
+    written: Option<(i64, bool)>,
+}
+
+impl CurrentTimer {
+    pub fn new(timer_type: &str, task: &str) -> CurrentTimer {
+        CurrentTimer {
+            path: history::data_dir().map(|dir| dir.join(CURRENT_FILE)),
+            timer_type: timer_type.to_string(),
+            task: task.to_string(),
+            started: Utc::now().timestamp(),
+            written: None,
+        }
+    }
+
+    /// Record the time left out of the `total` length. The file holds the end time,
+    /// so it only changes when the end moves: time added or removed, or a pause.
+    pub fn update(&mut self, remaining: u64, total: u64, paused: bool) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let ends_at = Utc::now().timestamp() + remaining as i64;
+        let unchanged = self.written.is_some_and(|(written, was_paused)| {
+            was_paused == paused && (paused || (written - ends_at).abs() <= 1)
+        });
+        if unchanged {
+            return;
+        }
+
+        let state = format!("{{\"type\":{},\"task\":{},\"started\":{},\"total\":{},\"ends_at\":{},\"remaining\":{},\"paused\":{},\"pid\":{}}}\n",
+                            json::string(&self.timer_type),
+                            json::string(&self.task),
+                            self.started,
+                            total,
+                            ends_at,
+                            remaining,
+                            paused,
+                            std::process::id());
+
+        // Write then rename, so readers never see half a file
+        let temp = path.with_extension("json.tmp");
+        let written = path.parent().is_some_and(|dir| fs::create_dir_all(dir).is_ok())
+            && fs::write(&temp, state).is_ok()
+            && fs::rename(&temp, path).is_ok();
+        if written {
+            self.written = Some((ends_at, paused));
+        }
+    }
+}
+
+impl Drop for CurrentTimer {
+    fn drop(&mut self) {
+        if let Some(path) = &self.path {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Seconds left on the running timer, or `None` if no timer is running
+pub fn remaining() -> Option<u64> {
+    running().map(|timer| timer.remaining)
+}
+
+/// The timer another pomodoro_rs process is running right now
+pub struct RunningTimer {
+    pub timer_type: String,
+    pub task: String,
+    /// Full length in seconds, with any time added or taken off
+    pub total: u64,
+    pub remaining: u64,
+    pub paused: bool,
+}
+
+/// The running timer, or `None` if no timer is running
+pub fn running() -> Option<RunningTimer> {
+    let path = history::data_dir()?.join(CURRENT_FILE);
+    let fields = json::parse_object(&fs::read_to_string(path).ok()?)?;
+
+    // A timer that was killed leaves its file behind
+    let pid: u32 = fields.get("pid")?.parse().ok()?;
+    if !process_alive(pid) {
+        return None;
+    }
+
+    let paused = fields.get("paused").is_some_and(|paused| paused == "true");
+    let remaining = if paused {
+        fields.get("remaining")?.parse().ok()?
+    } else {
+        let ends_at: i64 = fields.get("ends_at")?.parse().ok()?;
+        let left = ends_at - Utc::now().timestamp();
+        if left <= 0 {
+            return None;
+        }
+        left as u64
+    };
+
+    Some(RunningTimer {
+        timer_type: fields.get("type")?.clone(),
+        task: fields.get("task")?.clone(),
+        total: fields.get("total")?.parse().ok()?,
+        remaining,
+        paused,
+    })
+}
+
+/// A timer found in `current.json` after the process running it went away
+pub struct SavedTimer {
+    pub timer_type: String,
+    pub task: String,
+    pub started: DateTime<Local>,
+    /// Full length in seconds, with any time added or taken off
+    pub total: u64,
+    /// Seconds still to go; 0 if the end passed while nothing was running
+    pub remaining: u64,
+}
+
+/// The timer left behind by a crash or reboot, if any. A timer that's still
+/// running isn't returned, nor one stopped with Ctrl+C, which clears the file.
+pub fn saved_timer() -> Option<SavedTimer> {
+    let path = history::data_dir()?.join(CURRENT_FILE);
+    let fields = json::parse_object(&fs::read_to_string(path).ok()?)?;
+
+    let pid: u32 = fields.get("pid")?.parse().ok()?;
+    if process_alive(pid) {
+        return None;
+    }
+
+    let remaining = if fields.get("paused").is_some_and(|paused| paused == "true") {
+        fields.get("remaining")?.parse().ok()?
+    } else {
+        let ends_at: i64 = fields.get("ends_at")?.parse().ok()?;
+        (ends_at - Utc::now().timestamp()).max(0) as u64
+    };
+
+    Some(SavedTimer {
+        timer_type: fields.get("type")?.clone(),
+        task: fields.get("task")?.clone(),
+        started: Local.timestamp_opt(fields.get("started")?.parse().ok()?, 0).single()?,
+        total: fields.get("total")?.parse().ok()?,
+        remaining,
+    })
+}
+
+/// Forget the running timer, e.g. when exiting on Ctrl+C
+pub fn clear_current() {
+    if let Some(dir) = history::data_dir() {
+        let _ = fs::remove_file(dir.join(CURRENT_FILE));
+    }
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    // Signal 0 checks the process exists without touching it
+    unsafe { libc::kill(pid as libc::pid_t, 0) == 0 }
+}
+
+#[cfg(windows)]
+fn process_alive(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, STILL_ACTIVE};
+    use windows_sys::Win32::System::Threading::{GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
+
+    // A process that's gone can't be opened; one that's exited but still
+    // held open somewhere reports an exit code instead of STILL_ACTIVE
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return false;
+        }
+        let mut code = 0;
+        let queried = GetExitCodeProcess(handle, &mut code) != 0;
+        CloseHandle(handle);
+        queried && code == STILL_ACTIVE as u32
+    }
+}
+
+/// Without a cheap check, trust the file; a stale one still runs out at its end time
+#[cfg(not(any(unix, windows)))]
+fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+/// Whether presentation mode is on. Checked each time a sound or notification
+/// is due, so turning it on or off affects timers that are already running.
+pub fn presenting() -> bool {
+    history::data_dir().is_some_and(|dir| dir.join(PRESENTING_FILE).exists())
+}
+
+/// Turn presentation mode on or off for all timers
+pub fn set_presenting(on: bool) -> Result<(), String> {
+    let dir = history::data_dir().ok_or("could not determine the data directory")?;
+    let path = dir.join(PRESENTING_FILE);
+
+    if on {
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        fs::write(&path, "").map_err(|e| e.to_string())
+    } else {
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    }
+}
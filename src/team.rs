@@ -0,0 +1,205 @@
+use std::fs::{self, OpenOptions, create_dir_all};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use chrono::Local;
+use crate::{history, json};
+
+/// Completions waiting to be sent, one JSON object per line, under the data dir
+const OUTBOX_FILE: &str = "outbox.jsonl";
+
+/// Most events kept while offline; the oldest are dropped first
+const OUTBOX_LIMIT: usize = 500;
+
+/// Delays between attempts to send one event
+const BACKOFF_SECS: &[u64] = &[1, 2, 4];
+
+/// Longest we wait on exit for a flush that's still sending
+const EXIT_WAIT: Duration = Duration::from_secs(5);
+
+/// Held while the outbox file is read or rewritten
+static OUTBOX: Mutex<()> = Mutex::new(());
+
+/// Held by the thread currently flushing, so only one sends at a time
+static FLUSHING: Mutex<()> = Mutex::new(());
+
+/// Flush threads, waited on briefly before exit
+static PENDING: Mutex<Vec<JoinHandle<()>>> = Mutex::new(Vec::new());
+
+/// Where completions are posted and who they're credited to
+#[derive(Clone)]
+pub struct TeamSettings {
+    pub endpoint: String,
+    pub user: String,
+}
+
+/// Outcome of posting one event
+enum Delivery {
+    Sent,
+    /// The server refused it; retrying won't help
+    Rejected,
+    /// Network trouble or a server error; worth trying again later
+    Failed,
+}
+
+fn outbox_path() -> Option<PathBuf> {
+    history::data_dir().map(|dir| dir.join(OUTBOX_FILE))
+}
+
+/// Queue a completed session and try to send everything pending in the background
+pub fn record_completion(team: &TeamSettings, task_desc: &str, minutes: u64) {
+    let now = Local::now();
+    let event = format!(
+        "{{\"id\":{},\"user\":{},\"task\":{},\"minutes\":{},\"completed_at\":{}}}",
+        json::string(&format!("{}-{}", team.user, now.format("%Y%m%dT%H%M%S%.f"))),
+        json::string(&team.user),
+        json::string(task_desc),
+        minutes,
+        json::string(&now.to_rfc3339()));
+
+    queue(&event);
+    flush_in_background(team);
+}
+
+/// Append an event to the outbox, dropping the oldest ones past the limit
+fn queue(event: &str) {
+    let Some(path) = outbox_path() else {
+        return;
+    };
+    let _outbox = OUTBOX.lock().unwrap_or_else(|e| e.into_inner());
+
+    if let Some(dir) = path.parent()
+        && create_dir_all(dir).is_err() {
+        return;
+    }
+
+    let mut lines = read_lines(&path);
+    if lines.len() >= OUTBOX_LIMIT {
+        lines.drain(..=lines.len() - OUTBOX_LIMIT);
+        lines.push(event.to_string());
+        write_lines(&path, &lines);
+    } else if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&path) {
+        let _ = writeln!(file, "{}", event);
+    }
+}
+
+/// Send pending events on a background thread. Anything that can't be sent
+/// stays in the outbox for the next run, so exiting mid-flush loses nothing.
+pub fn flush_in_background(team: &TeamSettings) {
+    let endpoint = team.endpoint.clone();
+    let handle = thread::spawn(move || {
+        // Another thread is already on it and will pick up new events
+        let Ok(_flushing) = FLUSHING.try_lock() else {
+            return;
+        };
+        flush(&endpoint);
+    });
+
+    if let Ok(mut pending) = PENDING.lock() {
+        pending.retain(|handle| !handle.is_finished());
+        pending.push(handle);
+    }
+}
+
+/// Give a running flush a few seconds to finish before exit; whatever
+/// isn't sent by then is retried on the next run
+pub fn finish() {
+    let handles: Vec<_> = match PENDING.lock() {
+        Ok(mut pending) => pending.drain(..).collect(),
+        Err(_) => return,
+    };
+
+    let deadline = Instant::now() + EXIT_WAIT;
+    while handles.iter().any(|handle| !handle.is_finished()) && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Send events oldest first, stopping at the first one that still fails after retries
+fn flush(endpoint: &str) {
+    let Some(path) = outbox_path() else {
+        return;
+    };
+
+    loop {
+        let next = {
+            let _outbox = OUTBOX.lock().unwrap_or_else(|e| e.into_inner());
+            read_lines(&path).into_iter().next()
+        };
+        let Some(event) = next else {
+            return;
+        };
+
+        if let Delivery::Failed = send_with_retry(endpoint, &event) {
+            return;
+        }
+
+        // Sent or refused: either way it's done
+        let _outbox = OUTBOX.lock().unwrap_or_else(|e| e.into_inner());
+        let mut lines = read_lines(&path);
+        if let Some(index) = lines.iter().position(|line| *line == event) {
+            lines.remove(index);
+        }
+        write_lines(&path, &lines);
+    }
+}
+
+/// Post one event, backing off exponentially between attempts while it fails
+fn send_with_retry(endpoint: &str, event: &str) -> Delivery {
+    let mut result = send(endpoint, event);
+
+    for delay in BACKOFF_SECS {
+        if !matches!(result, Delivery::Failed) {
+            break;
+        }
+        thread::sleep(Duration::from_secs(*delay));
+        result = send(endpoint, event);
+    }
+
+    result
+}
+
+/// Post one event with `curl`
+fn send(endpoint: &str, event: &str) -> Delivery {
+    let null = if cfg!(windows) { "NUL" } else { "/dev/null" };
+
+    let output = Command::new("curl")
+        .args(["-sS", "-o", null, "-w", "%{http_code}", "--max-time", "10",
+               "-X", "POST", "-H", "Content-Type: application/json", "--data-binary", event, endpoint])
+        .output();
+
+    let Ok(output) = output else {
+        return Delivery::Failed;
+    };
+
+    match String::from_utf8_lossy(&output.stdout).trim().parse::<u16>() {
+        Ok(status) if (200..300).contains(&status) => Delivery::Sent,
+        // Rate limits and server errors are temporary
+        Ok(status) if status == 429 || status >= 500 => Delivery::Failed,
+        Ok(status) if status >= 400 => Delivery::Rejected,
+        // No response at all (offline, DNS, timeout)
+        _ => Delivery::Failed,
+    }
+}
+
+fn read_lines(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Replace the outbox contents, via a temp file so it's never left half-written
+fn write_lines(path: &Path, lines: &[String]) {
+    let temp = path.with_extension("jsonl.tmp");
+    let contents: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+
+    if fs::write(&temp, contents).is_ok() {
+        let _ = fs::rename(&temp, path);
+    }
+}
@@ -0,0 +1,158 @@
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use chrono::Local;
+use colored::*;
+use crate::history::{self, COMPLETED_DIR, Entry};
+
+/// Keys handled by the full-screen mode: pause/resume, skip and quit
+pub const PAUSE_KEY: char = 'p';
+pub const SKIP_KEY: char = 's';
+pub const QUIT_KEY: char = 'q';
+
+/// Most of today's sessions listed under the clock
+const SESSION_ROWS: usize = 8;
+
+/// Width of the progress bar under the clock
+const BAR_WIDTH: usize = 30;
+
+/// Set while the alternate screen is showing, so Ctrl+C can leave it
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// 3x5 pixel glyphs for `0`-`9`, then `:`
+const GLYPHS: [[&str; 5]; 11] = [
+    ["###", "# #", "# #", "# #", "###"],
+    ["  #", "  #", "  #", "  #", "  #"],
+    ["###", "  #", "###", "#  ", "###"],
+    ["###", "  #", "###", "  #", "###"],
+    ["# #", "# #", "###", "  #", "  #"],
+    ["###", "#  ", "###", "  #", "###"],
+    ["###", "#  ", "###", "# #", "###"],
+    ["###", "  #", "  #", "  #", "  #"],
+    ["###", "# #", "###", "# #", "###"],
+    ["###", "# #", "###", "  #", "###"],
+    [" ", "#", " ", "#", " "],
+];
+
+/// Full-screen timer view: a big clock, progress, and today's sessions.
+/// Drawn with plain ANSI escapes rather than ratatui, which isn't among our dependencies yet;
+/// swapping it in only touches this module. Leaves the alternate screen when dropped.
+pub struct Tui {
+    title: String,
+    is_break: bool,
+    today: Vec<Entry>,
+}
+
+impl Tui {
+    /// Switch to the alternate screen; `None` when stdout isn't a terminal
+    pub fn new(timer_type: &str, description: &str) -> Option<Tui> {
+        if !io::stdout().is_terminal() {
+            return None;
+        }
+
+        // Alternate screen, cursor hidden
+        print!("\x1b[?1049h\x1b[?25l");
+        io::stdout().flush().ok();
+        ACTIVE.store(true, Ordering::SeqCst);
+
+        Some(Tui {
+            title: format!("{} · {}", timer_type, description),
+            is_break: timer_type.contains("Break"),
+            today: history::read_day(COMPLETED_DIR, Local::now().date_naive()),
+        })
+    }
+
+    /// Redraw the whole screen; `note` is shown under the progress bar (e.g. "Paused").
+    /// With `remaining` as `None` (blind mode), the clock and progress are hidden.
+    pub fn draw(&self, remaining: Option<u64>, total: u64, eta: &str, note: Option<&str>) {
+        let mut lines = vec![String::new(), format!("  {}", self.title.bold()), String::new()];
+
+        match remaining {
+            Some(remaining) => {
+                let clock = big_text(&format!("{:02}:{:02}", remaining / 60, remaining % 60));
+                for row in clock {
+                    let row = if self.is_break { row.bright_green() } else { row.bright_red() };
+                    lines.push(format!("  {}", row));
+                }
+
+                let done = total.saturating_sub(remaining);
+                let filled = (done as usize * BAR_WIDTH).checked_div(total as usize).unwrap_or(BAR_WIDTH);
+                let percent = (done * 100).checked_div(total).unwrap_or(100);
+                lines.push(String::new());
+                lines.push(format!("  Ends at {}   {}{} {:>3}%",
+                                   eta.bright_cyan(),
+                                   "█".repeat(filled).green(),
+                                   "░".repeat(BAR_WIDTH - filled).dimmed(),
+                                   percent));
+            },
+            None => {
+                // Keep the layout steady so peeking doesn't make the screen jump
+                lines.extend([String::new(), String::new()]);
+                lines.push(format!("  {}", "⏳ working… (space to peek)".dimmed()));
+                lines.extend([String::new(), String::new(), String::new(), String::new()]);
+            },
+        }
+        lines.push(note.map(|note| format!("  {}", note.yellow())).unwrap_or_default());
+
+        let minutes: u64 = self.today.iter().filter_map(|entry| entry.minutes).sum();
+        lines.push(String::new());
+        lines.push(format!("  {} ({} sessions, {} min)", "Today".bold(), self.today.len(), minutes));
+        if self.today.is_empty() {
+            lines.push(format!("  {}", "Nothing logged yet".dimmed()));
+        }
+        let skip = self.today.len().saturating_sub(SESSION_ROWS);
+        for entry in &self.today[skip..] {
+            let length = entry.minutes.map(|m| format!(" ({} min)", m)).unwrap_or_default();
+            lines.push(format!("  {}  {}{}", entry.time.format("%H:%M").to_string().dimmed(), entry.task, length));
+        }
+
+        lines.push(String::new());
+        lines.push(format!("  {}", format!("{} pause/resume   {} skip   {} quit   +/- 5 min", PAUSE_KEY, SKIP_KEY, QUIT_KEY).dimmed()));
+
+        // Home the cursor and overwrite in place, clearing leftovers to avoid flicker
+        let mut frame = String::from("\x1b[H");
+        for line in lines {
+            frame.push_str(&line);
+            frame.push_str("\x1b[K\r\n");
+        }
+        frame.push_str("\x1b[J");
+
+        print!("{}", frame);
+        io::stdout().flush().ok();
+    }
+}
+
+impl Drop for Tui {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+/// Leave the alternate screen if it's showing (for use before exiting)
+pub fn restore() {
+    if ACTIVE.swap(false, Ordering::SeqCst) {
+        print!("\x1b[?25h\x1b[?1049l");
+        io::stdout().flush().ok();
+    }
+}
+
+/// Render digits and colons as five rows of block characters
+fn big_text(text: &str) -> Vec<String> {
+    let mut rows = vec![String::new(); 5];
+
+    for c in text.chars() {
+        let glyph = match c {
+            '0'..='9' => &GLYPHS[c as usize - '0' as usize],
+            ':' => &GLYPHS[10],
+            _ => continue,
+        };
+
+        for (row, pixels) in rows.iter_mut().zip(glyph) {
+            for pixel in pixels.chars() {
+                row.push_str(if pixel == '#' { "██" } else { "  " });
+            }
+            row.push(' ');
+        }
+    }
+
+    rows
+}
@@ -0,0 +1,62 @@
+use std::env;
+use std::process::{Command, Stdio};
+
+/// Title of the focused window, read with the platform's command-line tools:
+/// `xdotool` or `xprop` on X11, AppleScript on macOS (naming the app and its front window)
+pub fn active_title() -> Result<String, String> {
+    let title = if cfg!(target_os = "macos") {
+        run("osascript", &["-e", FRONT_WINDOW_SCRIPT])?
+    } else if cfg!(unix) {
+        if env::var_os("DISPLAY").is_none() {
+            return Err("no X11 display (Wayland and the console aren't supported)".to_string());
+        }
+        run("xdotool", &["getactivewindow", "getwindowname"]).or_else(|_| xprop_title())?
+    } else {
+        return Err("not supported on this platform".to_string());
+    };
+
+    let title = title.trim();
+    if title.is_empty() {
+        return Err("no window has focus".to_string());
+    }
+    Ok(title.to_string())
+}
+
+/// Front app, then " - " and its front window's title where it has one
+const FRONT_WINDOW_SCRIPT: &str = r#"tell application "System Events"
+    set frontApp to first application process whose frontmost is true
+    set appName to name of frontApp
+    try
+        return appName & " - " & (name of front window of frontApp)
+    on error
+        return appName
+    end try
+end tell"#;
+
+/// Ask the window manager for the active window, then that window for its name
+fn xprop_title() -> Result<String, String> {
+    // "_NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007"
+    let active = run("xprop", &["-root", "_NET_ACTIVE_WINDOW"])?;
+    let id = active.rsplit(' ').next().unwrap_or_default().trim().to_string();
+
+    // `_NET_WM_NAME(UTF8_STRING) = "title"`
+    let name = run("xprop", &["-id", &id, "_NET_WM_NAME"])?;
+    name.split_once(" = ")
+        .map(|(_, title)| title.trim().trim_matches('"').to_string())
+        .ok_or_else(|| "the active window has no name".to_string())
+}
+
+/// Standard output of `program`, if it ran and succeeded
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|_| format!("{} is not installed", program))?;
+
+    if !output.status.success() {
+        return Err(format!("{} exited with {}", program, output.status));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}